@@ -0,0 +1,187 @@
+use std::collections::VecDeque;
+
+/// Aho–Corasick automaton, finds all (also overlapping) occurrences of a set of
+/// patterns in a single pass over the haystack.
+///
+/// Works on bytes, since both patterns and haystack are valid UTF-8 a match can
+/// only start and end at char boundaries.
+pub struct AhoCorasick {
+    /// transitions[state][byte], failure links are already resolved into it
+    transitions: Vec<[u32; 256]>,
+    /// patterns ending in each state, including those reached via failure links
+    outputs: Vec<Vec<usize>>,
+    pattern_lens: Vec<usize>,
+    pattern_char_lens: Vec<usize>,
+}
+
+/// Occurrence of pattern number `pattern` at `haystack[start..end]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+    /// `start` counted in chars instead of bytes
+    pub char_start: usize,
+}
+
+impl AhoCorasick {
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        let mut automaton = AhoCorasick {
+            transitions: vec![[0; 256]],
+            outputs: vec![vec![]],
+            pattern_lens: vec![],
+            pattern_char_lens: vec![],
+        };
+
+        // build trie, 0 as transition means "none yet", the root can't be a target
+        for (pattern_nr, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref();
+            let mut state = 0;
+
+            for &b in pattern.as_bytes() {
+                if automaton.transitions[state][b as usize] == 0 {
+                    automaton.transitions.push([0; 256]);
+                    automaton.outputs.push(vec![]);
+                    automaton.transitions[state][b as usize] =
+                        (automaton.transitions.len() - 1) as u32;
+                }
+                state = automaton.transitions[state][b as usize] as usize;
+            }
+
+            automaton.outputs[state].push(pattern_nr);
+            automaton.pattern_lens.push(pattern.len());
+            automaton.pattern_char_lens.push(pattern.chars().count());
+        }
+
+        // breadth first over the trie, computing failure links and turning missing
+        // transitions into the transition of the failure state
+        let mut fail = vec![0; automaton.transitions.len()];
+        let mut queue: VecDeque<usize> = automaton.transitions[0]
+            .iter()
+            .filter(|&&next| next != 0)
+            .map(|&next| next as usize)
+            .collect();
+
+        while let Some(state) = queue.pop_front() {
+            let fail_outputs = automaton.outputs[fail[state]].clone();
+            automaton.outputs[state].extend(fail_outputs);
+
+            for b in 0..256 {
+                let next = automaton.transitions[state][b] as usize;
+                let fail_next = automaton.transitions[fail[state]][b];
+
+                if next == 0 {
+                    automaton.transitions[state][b] = fail_next;
+                } else {
+                    fail[next] = fail_next as usize;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        automaton
+    }
+
+    /// All matches, ordered by their end.
+    pub fn find_overlapping_iter<'a>(&'a self, haystack: &'a str) -> OverlappingMatches<'a> {
+        OverlappingMatches {
+            automaton: self,
+            haystack: haystack.as_bytes(),
+            pos: 0,
+            chars: 0,
+            state: 0,
+            pending: vec![],
+        }
+    }
+}
+
+/// Iterator created by [`AhoCorasick::find_overlapping_iter`].
+pub struct OverlappingMatches<'a> {
+    automaton: &'a AhoCorasick,
+    haystack: &'a [u8],
+    pos: usize,
+    chars: usize,
+    state: usize,
+    pending: Vec<Match>,
+}
+
+impl<'a> Iterator for OverlappingMatches<'a> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        while self.pending.is_empty() {
+            let &b = self.haystack.get(self.pos)?;
+            self.pos += 1;
+
+            // count every byte that is not a UTF-8 continuation byte
+            if b & 0b1100_0000 != 0b1000_0000 {
+                self.chars += 1;
+            }
+
+            self.state = self.automaton.transitions[self.state][b as usize] as usize;
+
+            // pending is used as a stack, pushed in reverse to yield the longest pattern first
+            for &pattern in self.automaton.outputs[self.state].iter().rev() {
+                self.pending.push(Match {
+                    pattern,
+                    start: self.pos - self.automaton.pattern_lens[pattern],
+                    end: self.pos,
+                    char_start: self.chars - self.automaton.pattern_char_lens[pattern],
+                });
+            }
+        }
+
+        self.pending.pop()
+    }
+}
+
+#[test]
+fn overlapping() {
+    let automaton = AhoCorasick::new(["one", "eight", "two", "ne", "1"]);
+
+    let matches: Vec<(usize, usize, usize)> = automaton
+        .find_overlapping_iter("oneightwo1")
+        .map(|m| (m.pattern, m.start, m.end))
+        .collect();
+
+    assert_eq!(
+        matches,
+        vec![(0, 0, 3), (3, 1, 3), (1, 2, 7), (2, 6, 9), (4, 9, 10)]
+    );
+    assert_eq!(automaton.find_overlapping_iter("xyz").next(), None);
+}
+
+#[test]
+fn utf8_offsets() {
+    let automaton = AhoCorasick::new(["fünf", "zéro", "ü"]);
+
+    let matches: Vec<Match> = automaton.find_overlapping_iter("ääfünfzéro").collect();
+
+    assert_eq!(
+        matches,
+        vec![
+            Match {
+                pattern: 2,
+                start: 5,
+                end: 7,
+                char_start: 3
+            },
+            Match {
+                pattern: 0,
+                start: 4,
+                end: 9,
+                char_start: 2
+            },
+            Match {
+                pattern: 1,
+                start: 9,
+                end: 14,
+                char_start: 6
+            },
+        ]
+    );
+}
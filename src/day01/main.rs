@@ -1,33 +1,72 @@
-use core::fmt::Write;
-use regex::Regex;
+use scanner::DigitScanner;
 use std::{error::Error, fs};
+use vocabulary::{DigitVocabulary, Language};
+
+mod scanner;
+mod vocabulary;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let input: String = fs::read_to_string("src/day01/input")?.parse()?;
-    part1(&input)?;
-    part2(&input)?;
+    let options = parse_args()?;
+
+    if options.explain {
+        explain(&input, &options.vocabulary);
+    } else {
+        part1(&input)?;
+        part2(&input, &options.vocabulary)?;
+    }
+
     Ok(())
 }
 
+struct Options {
+    vocabulary: DigitVocabulary,
+    explain: bool,
+}
+
+/// `--lang <language>` picks a built in vocabulary (english by default),
+/// `--vocabulary <file>` reads one from a file, `--zero` also counts zero (only
+/// the numeral `0` with a vocabulary file, its words spell zero if they want to),
+/// `--explain` shows the digits found in each line instead of the answers.
+fn parse_args() -> Result<Options, Box<dyn Error>> {
+    let mut language = Language::English;
+    let mut vocabulary = None;
+    let mut zero = false;
+    let mut explain = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lang" => language = args.next().ok_or("Missing language")?.parse()?,
+            "--vocabulary" => {
+                let path = args.next().ok_or("Missing vocabulary file")?;
+                vocabulary = Some(DigitVocabulary::from_file(&path)?);
+            }
+            "--zero" => zero = true,
+            "--explain" => explain = true,
+            _ => return Err(format!("Unknown argument: {}", arg).into()),
+        }
+    }
+
+    let vocabulary = match (vocabulary, zero) {
+        (Some(vocabulary), true) => vocabulary.with_alias("0", 0),
+        (Some(vocabulary), false) => vocabulary,
+        (None, true) => DigitVocabulary::builtin(language).with_zero(language),
+        (None, false) => DigitVocabulary::builtin(language),
+    };
+
+    Ok(Options {
+        vocabulary,
+        explain,
+    })
+}
+
 fn part1(input: &str) -> Result<(), Box<dyn Error>> {
+    let scanner = DigitScanner::new(&DigitVocabulary::numerals());
+
     let sum: u32 = input
         .lines()
-        .map(|line| -> u32 {
-            let first_digit = line
-                .chars()
-                .find(|x| x.is_digit(10))
-                .expect("Could not find first digit");
-
-            let last_digit = line
-                .chars()
-                .rev()
-                .find(|x| x.is_digit(10))
-                .expect("Could not find last digit");
-
-            let mut number = String::new();
-            let _ = write!(number, "{}{}", first_digit, last_digit);
-            number.parse::<u32>().unwrap()
-        })
+        .map(|line| calibration_value(line, &scanner).expect("Could not find first digit"))
         .sum();
 
     println!("{}", sum);
@@ -35,29 +74,12 @@ fn part1(input: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn part2(input: &str) -> Result<(), Box<dyn Error>> {
-    let re = Regex::new(r"[1-9]|one|two|three|four|five|six|seven|eight|nine").unwrap();
+fn part2(input: &str, vocabulary: &DigitVocabulary) -> Result<(), Box<dyn Error>> {
+    let scanner = DigitScanner::new(vocabulary);
 
     let sum: u32 = input
         .lines()
-        .map(|line| -> u32 {
-            let first_digit = re.find(line).unwrap().as_str();
-            let first_digit_parsed = parse_digit(first_digit);
-
-            // finding last digit, have to use ever increasing end string, because regex only finds non-overlapping matches beginning from the start
-            let mut last_digit_parsed = 0;
-            for i in (0..line.len()).rev() {
-                let end_string = &line[i..];
-                if let Some(last_digit) = re.find(end_string) {
-                    last_digit_parsed = parse_digit(last_digit.as_str());
-                    break;
-                };
-            }
-
-            let mut number = String::new();
-            let _ = write!(number, "{}{}", first_digit_parsed, last_digit_parsed);
-            number.parse::<u32>().unwrap()
-        })
+        .map(|line| calibration_value(line, &scanner).expect("Could not find digit"))
         .sum();
 
     println!("{}", sum);
@@ -65,19 +87,137 @@ fn part2(input: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn parse_digit(digit: &str) -> u8 {
-    let digits: Vec<String> = (1..=9).map(|x| x.to_string()).collect();
-    match digit {
-        x if digits.contains(&x.to_string()) => x.parse::<u8>().unwrap(),
-        "one" => 1,
-        "two" => 2,
-        "three" => 3,
-        "four" => 4,
-        "five" => 5,
-        "six" => 6,
-        "seven" => 7,
-        "eight" => 8,
-        "nine" => 9,
-        _ => { unreachable!() },
+fn calibration_value(line: &str, scanner: &DigitScanner) -> Option<u32> {
+    // words can overlap, e.g. "twone", so the last digit is not always found after the first one
+    let (first_digit, last_digit) = scanner.first_and_last(line)?;
+
+    Some(first_digit.digit as u32 * 10 + last_digit.digit as u32)
+}
+
+fn explain(input: &str, vocabulary: &DigitVocabulary) {
+    let numerals_scanner = DigitScanner::new(&DigitVocabulary::numerals());
+    let scanner = DigitScanner::new(vocabulary);
+    let mut part1_failures = 0;
+
+    for (line_nr, line) in input.lines().enumerate() {
+        let (text, part1_failed) = explain_line(line_nr + 1, line, &numerals_scanner, &scanner);
+        println!("{}", text);
+
+        if part1_failed {
+            part1_failures += 1;
+        }
+    }
+
+    if part1_failures > 0 {
+        println!(
+            "{} line(s) without numeral, part 1 would abort with \"Could not find first digit\"",
+            part1_failures
+        );
+    }
+}
+
+/// Line with the first (`^`) and last (`~`) word marked below it, `*` where they overlap.
+/// Also returns if part 1 would fail on the line.
+fn explain_line(
+    line_nr: usize,
+    line: &str,
+    numerals_scanner: &DigitScanner,
+    scanner: &DigitScanner,
+) -> (String, bool) {
+    let prefix = format!("{:>5}: ", line_nr);
+    let indent = " ".repeat(prefix.len());
+    let mut text = format!("{}{}\n", prefix, line);
+
+    match scanner.first_and_last(line) {
+        Some((first, last)) => {
+            let first_chars = first.char_offset..first.char_offset + first.word.chars().count();
+            let last_chars = last.char_offset..last.char_offset + last.word.chars().count();
+
+            let markers: String = (0..last_chars.end.max(first_chars.end))
+                .map(
+                    |i| match (first_chars.contains(&i), last_chars.contains(&i)) {
+                        (true, true) => '*',
+                        (true, false) => '^',
+                        (false, true) => '~',
+                        (false, false) => ' ',
+                    },
+                )
+                .collect();
+
+            text += &format!("{}{}\n", indent, markers);
+            text += &format!(
+                "{}first \"{}\" at byte {} (char {}), last \"{}\" at byte {} (char {}), value {}",
+                indent,
+                first.word,
+                first.byte_offset,
+                first.char_offset,
+                last.word,
+                last.byte_offset,
+                last.char_offset,
+                first.digit as u32 * 10 + last.digit as u32
+            );
+        }
+        None => text += &format!("{}no digit found", indent),
     }
+
+    let part1_failed = numerals_scanner.first_and_last(line).is_none();
+    if part1_failed {
+        text += &format!("\n{}part 1: Could not find first digit", indent);
+    }
+
+    (text, part1_failed)
+}
+
+#[test]
+fn examples() {
+    let example_input = "
+        two1nine
+        eightwothree
+        abcone2threexyz
+        xtwone3four
+        4nineeightseven2
+        zoneight234
+        7pqrstsixteen
+    ";
+
+    let scanner = DigitScanner::new(&DigitVocabulary::builtin(Language::English));
+    let values: Vec<u32> = example_input
+        .trim()
+        .lines()
+        .map(|line| calibration_value(line.trim(), &scanner).unwrap())
+        .collect();
+    assert_eq!(values, vec![29, 83, 13, 24, 42, 14, 76]);
+
+    let german = DigitVocabulary::builtin(Language::German);
+    let scanner = DigitScanner::new(&german);
+    assert_eq!(calibration_value("xfünfzehnachtzig", &scanner), Some(58));
+    assert_eq!(calibration_value("dreinull", &scanner), Some(33));
+
+    let scanner = DigitScanner::new(&german.with_zero(Language::German));
+    assert_eq!(calibration_value("dreinull", &scanner), Some(30));
+}
+
+#[test]
+fn explanations() {
+    let numerals_scanner = DigitScanner::new(&DigitVocabulary::numerals());
+    let scanner = DigitScanner::new(&DigitVocabulary::builtin(Language::English));
+
+    let (text, part1_failed) = explain_line(4, "xtwone3four", &numerals_scanner, &scanner);
+    assert!(!part1_failed);
+    assert_eq!(
+        text,
+        "    4: xtwone3four
+        ^^^   ~~~~
+       first \"two\" at byte 1 (char 1), last \"four\" at byte 7 (char 7), value 24"
+    );
+
+    let (text, part1_failed) = explain_line(12, "eightwo", &numerals_scanner, &scanner);
+    assert!(part1_failed);
+    assert_eq!(
+        text,
+        "   12: eightwo
+       ^^^^*~~
+       first \"eight\" at byte 0 (char 0), last \"two\" at byte 4 (char 4), value 82
+       part 1: Could not find first digit"
+    );
 }
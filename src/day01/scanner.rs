@@ -0,0 +1,84 @@
+use crate::vocabulary::DigitVocabulary;
+use advent_of_code_2023::aho_corasick::AhoCorasick;
+use std::cmp::Reverse;
+
+/// Word of the vocabulary found in a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitMatch<'a> {
+    pub word: &'a str,
+    pub digit: u8,
+    pub byte_offset: usize,
+    pub char_offset: usize,
+}
+
+/// Finds the words of a [`DigitVocabulary`] in a single pass, also when they overlap.
+pub struct DigitScanner {
+    automaton: AhoCorasick,
+    digits: Vec<u8>,
+}
+
+impl DigitScanner {
+    pub fn new(vocabulary: &DigitVocabulary) -> Self {
+        DigitScanner {
+            automaton: AhoCorasick::new(vocabulary.words().map(|(word, _)| word)),
+            digits: vocabulary.words().map(|(_, digit)| digit).collect(),
+        }
+    }
+
+    /// First and last word in `line`, e.g. "two" and "one" in "xtwone".
+    /// Of words starting at the same position the longest one counts.
+    pub fn first_and_last<'a>(&self, line: &'a str) -> Option<(DigitMatch<'a>, DigitMatch<'a>)> {
+        let mut first: Option<DigitMatch> = None;
+        let mut last: Option<DigitMatch> = None;
+
+        for m in self.automaton.find_overlapping_iter(line) {
+            let found = DigitMatch {
+                word: &line[m.start..m.end],
+                digit: self.digits[m.pattern],
+                byte_offset: m.start,
+                char_offset: m.char_start,
+            };
+
+            // matches come ordered by their end, so compare the starts
+            if first.is_none_or(|f| {
+                (found.byte_offset, Reverse(found.word.len()))
+                    < (f.byte_offset, Reverse(f.word.len()))
+            }) {
+                first = Some(found);
+            }
+            if last.is_none_or(|l| {
+                (found.byte_offset, found.word.len()) > (l.byte_offset, l.word.len())
+            }) {
+                last = Some(found);
+            }
+        }
+
+        first.zip(last)
+    }
+}
+
+#[test]
+fn overlapping_words() {
+    let scanner = DigitScanner::new(&DigitVocabulary::builtin(
+        crate::vocabulary::Language::English,
+    ));
+
+    let (first, last) = scanner.first_and_last("xtwone3fourighthree").unwrap();
+    assert_eq!((first.word, first.digit, first.byte_offset), ("two", 2, 1));
+    assert_eq!((last.word, last.digit, last.byte_offset), ("three", 3, 14));
+    assert_eq!(scanner.first_and_last("abc"), None);
+
+    // byte and char offsets differ after non-ASCII chars
+    let (first, last) = scanner.first_and_last("äöüsevenßeightwoé").unwrap();
+    assert_eq!((first.byte_offset, first.char_offset), (6, 3));
+    assert_eq!(
+        (last.word, last.byte_offset, last.char_offset),
+        ("two", 17, 13)
+    );
+
+    // multi megabyte line
+    let line = format!("nine{}eightwo", "ä-x".repeat(2_000_000));
+    let (first, last) = scanner.first_and_last(&line).unwrap();
+    assert_eq!((first.digit, last.digit), (9, 2));
+    assert_eq!(last.char_offset, 6_000_008);
+}
@@ -0,0 +1,182 @@
+use std::{error::Error, fs, str::FromStr};
+
+/// Languages with built in spelled digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Dutch,
+}
+
+impl Language {
+    /// The spelled digits from zero to nine.
+    pub fn words(&self) -> [&'static str; 10] {
+        match self {
+            Language::English => [
+                "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+            ],
+            Language::German => [
+                "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+            ],
+            Language::French => [
+                "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+            ],
+            Language::Spanish => [
+                "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+            ],
+            Language::Italian => [
+                "zero", "uno", "due", "tre", "quattro", "cinque", "sei", "sette", "otto", "nove",
+            ],
+            Language::Dutch => [
+                "nul", "een", "twee", "drie", "vier", "vijf", "zes", "zeven", "acht", "negen",
+            ],
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" => Ok(Language::English),
+            "de" | "german" => Ok(Language::German),
+            "fr" | "french" => Ok(Language::French),
+            "es" | "spanish" => Ok(Language::Spanish),
+            "it" | "italian" => Ok(Language::Italian),
+            "nl" | "dutch" => Ok(Language::Dutch),
+            _ => Err(format!("Unknown language: {}", s)),
+        }
+    }
+}
+
+/// Words that count as a digit in a calibration line, e.g. "7" or "seven".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitVocabulary {
+    words: Vec<(String, u8)>,
+}
+
+impl DigitVocabulary {
+    /// The numerals `0` to `9`, as in part 1.
+    pub fn numerals() -> Self {
+        DigitVocabulary { words: vec![] }.with_numerals(0..=9)
+    }
+
+    /// The numerals `1` to `9` and the spelled digits one to nine in `language`, as in part 2.
+    pub fn builtin(language: Language) -> Self {
+        let mut vocabulary = DigitVocabulary { words: vec![] }.with_numerals(1..=9);
+
+        for (digit, word) in language.words().into_iter().enumerate().skip(1) {
+            vocabulary = vocabulary.with_alias(word, digit as u8);
+        }
+
+        vocabulary
+    }
+
+    /// Also counts zero, as numeral and spelled in `language`.
+    pub fn with_zero(self, language: Language) -> Self {
+        self.with_alias("0", 0).with_alias(language.words()[0], 0)
+    }
+
+    /// Adds `word` as another way to write `digit`.
+    pub fn with_alias(mut self, word: &str, digit: u8) -> Self {
+        assert!(digit <= 9, "{} is not a digit", digit);
+
+        if !self.words.iter().any(|(w, _)| w == word) {
+            self.words.push((word.to_owned(), digit));
+        }
+
+        self
+    }
+
+    fn with_numerals(mut self, digits: impl Iterator<Item = u8>) -> Self {
+        for digit in digits {
+            self = self.with_alias(&digit.to_string(), digit);
+        }
+
+        self
+    }
+
+    /// Reads the numerals `1` to `9` plus one `word = digit` line per word,
+    /// empty lines and lines starting with `#` are ignored. A word can't stand
+    /// for two different digits.
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut vocabulary = DigitVocabulary { words: vec![] }.with_numerals(1..=9);
+
+        for (line_nr, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (word, digit) = line
+                .split_once('=')
+                .map(|(word, digit)| (word.trim(), digit.trim().parse::<u8>()))
+                .filter(|(word, digit)| !word.is_empty() && matches!(digit, Ok(0..=9)))
+                .ok_or(format!(
+                    "Line {}: expected `word = digit`, found `{}`",
+                    line_nr + 1,
+                    line
+                ))?;
+
+            let digit = digit?;
+            if let Some((_, known)) = vocabulary.words().find(|(w, d)| *w == word && *d != digit) {
+                return Err(format!(
+                    "Line {}: `{}` is already {}, can't also be {}",
+                    line_nr + 1,
+                    word,
+                    known,
+                    digit
+                )
+                .into());
+            }
+
+            vocabulary = vocabulary.with_alias(word, digit);
+        }
+
+        Ok(vocabulary)
+    }
+
+    /// All words with the digit they stand for.
+    pub fn words(&self) -> impl Iterator<Item = (&str, u8)> {
+        self.words
+            .iter()
+            .map(|(word, digit)| (word.as_str(), *digit))
+    }
+}
+
+#[test]
+fn vocabularies() {
+    let german = DigitVocabulary::builtin(Language::German).with_alias("ein", 1);
+    let digit = |vocabulary: &DigitVocabulary, word| {
+        vocabulary.words().find(|(w, _)| *w == word).map(|(_, d)| d)
+    };
+
+    assert_eq!(digit(&german, "fünf"), Some(5));
+    assert_eq!(digit(&german, "ein"), Some(1));
+    assert_eq!(digit(&german, "null"), None);
+    assert_eq!(digit(&german.with_zero(Language::German), "null"), Some(0));
+
+    let custom = DigitVocabulary::parse("# roman\ni = 1\nv = 5\n\nx=0").unwrap();
+    assert_eq!(digit(&custom, "v"), Some(5));
+    assert_eq!(digit(&custom, "7"), Some(7));
+    assert!(DigitVocabulary::parse("eleven = 11").is_err());
+    assert!(DigitVocabulary::parse("one two").is_err());
+
+    // repeating a word is fine, giving it another digit is not
+    assert!(DigitVocabulary::parse("i = 1\ni = 1").is_ok());
+    assert_eq!(
+        DigitVocabulary::parse("i = 1\nv = 5\ni = 7")
+            .unwrap_err()
+            .to_string(),
+        "Line 3: `i` is already 1, can't also be 7"
+    );
+    assert!(DigitVocabulary::parse("3 = 4").is_err());
+}
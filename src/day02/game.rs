@@ -0,0 +1,248 @@
+use advent_of_code_2023::parsing::split_header;
+use std::{collections::HashMap, fmt, str::FromStr};
+
+pub type Colour = String;
+
+/// Cubes shown at once, by colour.
+pub type Draw = HashMap<Colour, u32>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    /// Could every draw have come from `bag`?
+    pub fn is_feasible(&self, bag: &Bag) -> bool {
+        self.draws.iter().all(|draw| {
+            draw.iter()
+                .all(|(colour, &count)| count <= bag.count(colour))
+        })
+    }
+
+    /// Smallest bag the game could have been played with, the maximum per colour over all draws.
+    pub fn minimal_bag(&self) -> Bag {
+        let mut bag = Bag {
+            cubes: HashMap::new(),
+        };
+
+        for draw in &self.draws {
+            bag.extend_to(draw);
+        }
+
+        bag
+    }
+
+    /// Colour with the least cubes to spare in `bag` and that number, negative if
+    /// the game is not feasible. Ties go to the alphabetically first colour.
+    pub fn binding_colour(&self, bag: &Bag) -> Option<(Colour, i64)> {
+        self.minimal_bag()
+            .cubes
+            .into_iter()
+            .map(|(colour, count)| {
+                let slack = bag.count(&colour) as i64 - count as i64;
+                (colour, slack)
+            })
+            .min_by(|(colour_a, slack_a), (colour_b, slack_b)| {
+                slack_a.cmp(slack_b).then(colour_a.cmp(colour_b))
+            })
+    }
+}
+
+impl FromStr for Game {
+    type Err = String;
+
+    /// `Game 3: 8 green, 6 blue; 5 blue, 4 red`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, draws_str) = split_header(s).map_err(|_| "Missing `Game N:` label")?;
+
+        let id = label
+            .strip_prefix("Game")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or(format!("Invalid game label `{}`", label))?;
+
+        let draws = draws_str
+            .split(';')
+            .map(parse_cubes)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Game { id, draws })
+    }
+}
+
+/// The cubes in the bag, by colour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bag {
+    pub cubes: HashMap<Colour, u32>,
+}
+
+impl Bag {
+    /// Number of cubes of `colour`, 0 if the bag has none.
+    pub fn count(&self, colour: &str) -> u32 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    /// Product of the cube counts of the colours in `colours`, a colour this bag
+    /// doesn't have makes it 0. `None` if the product doesn't fit into u64.
+    pub fn power(&self, colours: &Bag) -> Option<u64> {
+        colours.cubes.keys().try_fold(1u64, |product, colour| {
+            product.checked_mul(self.count(colour) as u64)
+        })
+    }
+
+    /// Smallest bag every game in `games` is feasible with.
+    pub fn smallest_common(games: &[Game]) -> Bag {
+        let mut bag = Bag {
+            cubes: HashMap::new(),
+        };
+
+        for game in games {
+            bag.extend_to(&game.minimal_bag().cubes);
+        }
+
+        bag
+    }
+
+    /// Adds cubes until there are at least as many of each colour as in `cubes`.
+    fn extend_to(&mut self, cubes: &HashMap<Colour, u32>) {
+        for (colour, &count) in cubes {
+            let max = self.cubes.entry(colour.clone()).or_insert(0);
+            *max = u32::max(*max, count);
+        }
+    }
+
+    /// (colour, count) sorted by colour.
+    pub fn sorted(&self) -> Vec<(&str, u32)> {
+        let mut cubes: Vec<(&str, u32)> = self
+            .cubes
+            .iter()
+            .map(|(colour, &count)| (colour.as_str(), count))
+            .collect();
+        cubes.sort();
+        cubes
+    }
+}
+
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes: Vec<String> = self
+            .sorted()
+            .iter()
+            .map(|(colour, count)| format!("{} {}", count, colour))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+impl FromStr for Bag {
+    type Err = String;
+
+    /// `12 red, 13 green, 14 blue`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Bag {
+            cubes: parse_cubes(s)?,
+        })
+    }
+}
+
+/// `3 blue, 4 red`, every colour may appear only once.
+fn parse_cubes(s: &str) -> Result<HashMap<Colour, u32>, String> {
+    let mut cubes = HashMap::new();
+
+    for cube_str in s.split(',') {
+        let (count, colour) = cube_str
+            .trim()
+            .split_once(' ')
+            .and_then(|(count, colour)| Some((count.parse().ok()?, colour.trim())))
+            // words of letters with single spaces, so a missing comma is not taken as colour
+            .filter(|(_, colour): &(u32, &str)| {
+                colour
+                    .split(' ')
+                    .all(|word| !word.is_empty() && word.chars().all(char::is_alphabetic))
+            })
+            .ok_or(format!(
+                "Expected `<count> <colour>`, found `{}`",
+                cube_str.trim()
+            ))?;
+
+        if cubes.insert(colour.to_owned(), count).is_some() {
+            return Err(format!(
+                "Colour `{}` appears twice in `{}`",
+                colour,
+                s.trim()
+            ));
+        }
+    }
+
+    Ok(cubes)
+}
+
+/// One game per line, errors mention the line number.
+pub fn parse_games(input: &str) -> Result<Vec<Game>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_nr, line)| {
+            line.trim()
+                .parse()
+                .map_err(|e| format!("Line {}: {}", line_nr + 1, e))
+        })
+        .collect()
+}
+
+#[test]
+fn parse_errors() {
+    let game: Game = "Game 12: 3 blue, 4 red; 2 purple".parse().unwrap();
+    assert_eq!(game.id, 12);
+    assert_eq!(game.draws[1]["purple"], 2);
+
+    assert!("3 blue, 4 red".parse::<Game>().is_err());
+    assert!("Round 1: 3 blue".parse::<Game>().is_err());
+    assert!("Game x: 3 blue".parse::<Game>().is_err());
+    assert!("Game 1: 3 blue; 4".parse::<Game>().is_err());
+    assert!("Game 1: blue 3".parse::<Game>().is_err());
+    assert!("Game 1: 3 blue, 1 blue".parse::<Game>().is_err());
+    assert!("Game 1: -3 blue".parse::<Game>().is_err());
+    assert_eq!(
+        "Game 1: 15 blue 4 red".parse::<Game>().unwrap_err(),
+        "Expected `<count> <colour>`, found `15 blue 4 red`"
+    );
+    assert!("Game 1: 1 light  blue".parse::<Game>().is_err());
+    assert_eq!(
+        "Game 1: 1 light blue".parse::<Game>().unwrap().draws[0]["light blue"],
+        1
+    );
+
+    let err = parse_games("Game 1: 1 red\nGame 2: 1 red,").unwrap_err();
+    assert!(err.starts_with("Line 2:"));
+}
+
+#[test]
+fn bag_queries() {
+    let games = parse_games(
+        "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+    )
+    .unwrap();
+    let bag: Bag = "12 red, 13 green, 14 blue".parse().unwrap();
+
+    assert_eq!(games[0].binding_colour(&bag), Some(("blue".to_owned(), 8)));
+    assert_eq!(games[1].binding_colour(&bag), Some(("red".to_owned(), -8)));
+    assert_eq!(games[0].minimal_bag().to_string(), "6 blue, 2 green, 4 red");
+    assert_eq!(
+        Bag::smallest_common(&games).to_string(),
+        "6 blue, 13 green, 20 red"
+    );
+
+    // a colour that is never shown counts as 0 cubes
+    let no_green: Game = "Game 1: 3 blue, 4 red".parse().unwrap();
+    assert_eq!(no_green.minimal_bag().power(&bag), Some(0));
+    assert_eq!(games[1].minimal_bag().power(&bag), Some(20 * 13 * 6));
+
+    let huge: Bag = "4000000000 red, 4000000000 green, 4000000000 blue"
+        .parse()
+        .unwrap();
+    assert_eq!(huge.power(&bag), None);
+}
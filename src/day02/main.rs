@@ -1,80 +1,94 @@
-use regex::Regex;
+use game::{parse_games, Bag, Game};
 use std::{error::Error, fs};
 
+mod game;
+mod report;
+
 fn main() -> Result<(), Box<dyn Error>> {
     let input: String = fs::read_to_string("src/day02/input")?.parse()?;
-    part1(&input)?;
-    part2(&input)?;
+    let games = parse_games(&input)?;
+    let options = parse_args()?;
+
+    match options.report.as_deref() {
+        None => {
+            part1(&games, &options.bag);
+            part2(&games, &options.bag)?;
+        }
+        Some("table") => println!("{}", report::table(&games, &options.bag)),
+        Some("json") => println!("{}", report::json(&games, &options.bag)),
+        Some(format) => return Err(format!("Unknown report format: {}", format).into()),
+    }
+
     Ok(())
 }
 
-fn part1(input: &str) -> Result<(), Box<dyn Error>> {
-    let red_re: Regex = Regex::new(r"(\d+) red").unwrap();
-    let green_re: Regex = Regex::new(r"(\d+) green").unwrap();
-    let blue_re: Regex = Regex::new(r"(\d+) blue").unwrap();
-
-    const MAX_RED: i32 = 12;
-    const MAX_GREEN: i32 = 13;
-    const MAX_BLUE: i32 = 14;
-
-    let sum: usize = input
-        .lines()
-        .enumerate()
-        .map(|(i, game)| {
-            let cube_sets = game.split(";");
-
-            for cube_set in cube_sets {
-                let red = get_number_from_string(&red_re, cube_set);
-                let green = get_number_from_string(&green_re, cube_set);
-                let blue = get_number_from_string(&blue_re, cube_set);
-                if red > MAX_RED || green > MAX_GREEN || blue > MAX_BLUE {
-                    return 0;
-                }
-            }
-
-            i + 1
-        })
+struct Options {
+    bag: Bag,
+    report: Option<String>,
+}
+
+/// `--bag "12 red, 13 green, 14 blue"` sets the bag for part 1, this one is the default.
+/// `--report table|json` shows feasibility, binding colour and minimal bag per game instead.
+fn parse_args() -> Result<Options, Box<dyn Error>> {
+    let mut bag = "12 red, 13 green, 14 blue".to_owned();
+    let mut report = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bag" => bag = args.next().ok_or("Missing bag")?,
+            "--report" => report = Some(args.next().ok_or("Missing report format")?),
+            _ => return Err(format!("Unknown argument: {}", arg).into()),
+        }
+    }
+
+    Ok(Options {
+        bag: bag.parse()?,
+        report,
+    })
+}
+
+fn part1(games: &[Game], bag: &Bag) -> u32 {
+    let sum: u32 = games
+        .iter()
+        .filter(|game| game.is_feasible(bag))
+        .map(|game| game.id)
         .sum();
 
     println!("{}", sum);
-    Ok(())
+    sum
 }
 
-fn part2(input: &str) -> Result<(), Box<dyn Error>> {
-    let red_re: Regex = Regex::new(r"(\d+) red").unwrap();
-    let green_re: Regex = Regex::new(r"(\d+) green").unwrap();
-    let blue_re: Regex = Regex::new(r"(\d+) blue").unwrap();
-
-    let sum: i32 = input
-        .lines()
-        .map(|game| {
-            let cube_sets = game.split(";");
-            let mut max_red = 0;
-            let mut max_green = 0;
-            let mut max_blue = 0;
-
-            for cube_set in cube_sets {
-                let red = get_number_from_string(&red_re, cube_set);
-                let green = get_number_from_string(&green_re, cube_set);
-                let blue = get_number_from_string(&blue_re, cube_set);
-
-                max_red = i32::max(max_red, red);
-                max_green = i32::max(max_green, green);
-                max_blue = i32::max(max_blue, blue);
-            }
-
-            max_red * max_green * max_blue
-        })
-        .sum();
+/// The power is taken over the colours of `bag`, so a game without one of them adds 0.
+fn part2(games: &[Game], bag: &Bag) -> Result<u64, String> {
+    let sum = games.iter().try_fold(0u64, |sum, game| {
+        game.minimal_bag()
+            .power(bag)
+            .and_then(|power| sum.checked_add(power))
+            .ok_or(format!("Power of game {} overflows", game.id))
+    })?;
 
     println!("{}", sum);
-    Ok(())
+    Ok(sum)
 }
 
-fn get_number_from_string(re: &Regex, s: &str) -> i32 {
-    if let Some(x) = re.captures(s) {
-        x.get(1).unwrap().as_str().parse::<i32>().unwrap()
-    } else {
-        0
-    }
+#[test]
+fn examples() {
+    let example_input = "
+        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+    ";
+
+    let games = parse_games(example_input).unwrap();
+    let bag: Bag = "12 red, 13 green, 14 blue".parse().unwrap();
+
+    assert_eq!(part1(&games, &bag), 8);
+    assert_eq!(part2(&games, &bag), Ok(2286));
+
+    // as in the original solution, a colour that is never shown counts as 0
+    let no_blue = parse_games("Game 1: 3 red, 4 green; 1 red").unwrap();
+    assert_eq!(part2(&no_blue, &bag), Ok(0));
 }
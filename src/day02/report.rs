@@ -0,0 +1,132 @@
+use crate::game::{Bag, Colour, Game};
+
+/// What a bag means for a single game.
+pub struct GameReport {
+    pub id: u32,
+    pub feasible: bool,
+    pub binding: Option<(Colour, i64)>,
+    pub minimal_bag: Bag,
+}
+
+pub fn game_reports(games: &[Game], bag: &Bag) -> Vec<GameReport> {
+    games
+        .iter()
+        .map(|game| GameReport {
+            id: game.id,
+            feasible: game.is_feasible(bag),
+            binding: game.binding_colour(bag),
+            minimal_bag: game.minimal_bag(),
+        })
+        .collect()
+}
+
+pub fn table(games: &[Game], bag: &Bag) -> String {
+    let mut text = format!("bag: {}\n\n", bag);
+    text += &format!(
+        "{:>6}  {:<8}  {:<20}  {}\n",
+        "game", "feasible", "binding (slack)", "minimal bag"
+    );
+
+    for report in game_reports(games, bag) {
+        let binding = match &report.binding {
+            Some((colour, slack)) => format!("{} ({})", colour, slack),
+            None => "-".to_owned(),
+        };
+
+        text += &format!(
+            "{:>6}  {:<8}  {:<20}  {}\n",
+            report.id,
+            if report.feasible { "yes" } else { "no" },
+            binding,
+            report.minimal_bag
+        );
+    }
+
+    text += &format!(
+        "\nsmallest bag for all games: {}",
+        Bag::smallest_common(games)
+    );
+    text
+}
+
+pub fn json(games: &[Game], bag: &Bag) -> String {
+    let reports: Vec<String> = game_reports(games, bag)
+        .iter()
+        .map(|report| {
+            let binding = match &report.binding {
+                Some((colour, slack)) => {
+                    format!(r#"{{"colour":{},"slack":{}}}"#, json_string(colour), slack)
+                }
+                None => "null".to_owned(),
+            };
+
+            format!(
+                r#"{{"id":{},"feasible":{},"binding":{},"minimal_bag":{}}}"#,
+                report.id,
+                report.feasible,
+                binding,
+                json_bag(&report.minimal_bag)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{{"bag":{},"games":[{}],"smallest_common_bag":{}}}"#,
+        json_bag(bag),
+        reports.join(","),
+        json_bag(&Bag::smallest_common(games))
+    )
+}
+
+fn json_bag(bag: &Bag) -> String {
+    let cubes: Vec<String> = bag
+        .sorted()
+        .iter()
+        .map(|(colour, count)| format!("{}:{}", json_string(colour), count))
+        .collect();
+    format!("{{{}}}", cubes.join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            c if c.is_control() => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[test]
+fn reports() {
+    let games = crate::game::parse_games(
+        "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 3: 8 green, 6 blue, 20 red; 5 light blue",
+    )
+    .unwrap();
+    let bag: Bag = "12 red, 13 green, 14 blue".parse().unwrap();
+
+    assert_eq!(
+        table(&games, &bag),
+        "bag: 14 blue, 13 green, 12 red
+
+  game  feasible  binding (slack)       minimal bag
+     1  yes       blue (8)              6 blue, 2 green, 4 red
+     3  no        red (-8)              6 blue, 8 green, 5 light blue, 20 red
+
+smallest bag for all games: 6 blue, 8 green, 5 light blue, 20 red"
+    );
+
+    assert_eq!(
+        json(&games, &bag),
+        r#"{"bag":{"blue":14,"green":13,"red":12},"games":[{"id":1,"feasible":true,"binding":{"colour":"blue","slack":8},"minimal_bag":{"blue":6,"green":2,"red":4}},{"id":3,"feasible":false,"binding":{"colour":"red","slack":-8},"minimal_bag":{"blue":6,"green":8,"light blue":5,"red":20}}],"smallest_common_bag":{"blue":6,"green":8,"light blue":5,"red":20}}"#
+    );
+    // parsed colours are only letters and spaces, so escaping is checked directly
+    assert_eq!(json_string("\"teal\"\\\n"), r#""\"teal\"\\\u000a""#);
+}
@@ -1,115 +1,165 @@
-use regex::{Match, Regex};
-use std::{collections::HashSet, error::Error, fs};
+use rules::Rules;
+use schematic::Schematic;
+use std::{error::Error, fs};
+
+mod render;
+mod rules;
+mod schematic;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let input: String = fs::read_to_string("src/day03/input")?.parse()?;
-    part1(&input)?;
-    part2(&input)?;
+    let options = parse_args()?;
+    let rules = options.rules;
+    let schematic = Schematic::parse(&input, rules.adjacency)?;
+
+    if options.render {
+        print!("{}", render::render(&schematic, &rules, options.colour));
+    } else {
+        part1(&schematic, &rules)?;
+        part2(&schematic, &rules)?;
+    }
 
     Ok(())
 }
 
-fn part1(input: &str) -> Result<(), Box<dyn Error>> {
-    #[derive(PartialEq, Eq, Hash, Debug)]
-    struct Number {
-        value: u32,
-        x: usize,
-        y: usize,
-    }
-
-    let numbers_re = Regex::new(r"\d+").unwrap();
-    let symbols_re = Regex::new(r"[^\d\.\n]").unwrap();
-    let mut numbers_vec: Vec<Vec<Match>> = vec![];
-    let mut symbols_vec: Vec<Vec<Match>> = vec![];
-    let mut relevant_numbers_set: HashSet<Number> = HashSet::new();
-
-    // find numbers and symbols in each line and put them in corresponding vector
-    input.lines().for_each(|line| {
-        let numbers: Vec<Match> = numbers_re.find_iter(line).collect();
-        numbers_vec.push(numbers);
-        let symbols: Vec<Match> = symbols_re.find_iter(line).collect();
-        symbols_vec.push(symbols);
-    });
-
-    // iterate over found symbols
-    for (symbol_y, symbol_line) in symbols_vec.iter().enumerate() {
-        for symbol in symbol_line {
-            let symbol_x = symbol.start();
-
-            // iterate over numbers in relevant lines [y-1, y, y+1]
-            let start_y = symbol_y.checked_sub(1).unwrap_or_default(); // not less than 0
-            let end_y = usize::min(numbers_vec.len() - 1, symbol_y + 1); // not more than numbers_vec.len()
-
-            for (number_y, number_line) in numbers_vec[start_y..=end_y].iter().enumerate() {
-                for number in number_line {
-                    // check if symbol is close to number in x-direction, y is already filtered to relevant only
-                    if symbol_x >= number.start().checked_sub(1).unwrap_or_default()
-                        && symbol_x <= number.end()
-                    // here no +1 because Match.end() uses exclusive end of range
-                    {
-                        relevant_numbers_set.insert(Number {
-                            value: number.as_str().parse().unwrap(),
-                            x: number.start(),
-                            y: number_y + start_y,
-                        });
-                    }
-                }
+/// Without arguments the rules of the puzzle apply, each flag replaces one of them:
+/// `--parts any|*#|!*#` symbols that make part numbers,
+/// `--gears *` symbols that can be gears,
+/// `--neighbours 2|2+|2-4` numbers a gear has to touch,
+/// `--ratio product|sum|max` how those numbers are combined,
+/// `--adjacency 8|4|radius=k` when a symbol touches a number.
+///
+/// `--render` shows the schematic coloured by these rules instead of the answers,
+/// `--no-color` marks the cells with letters instead.
+fn parse_args() -> Result<Options, Box<dyn Error>> {
+    let mut rules = Rules::default();
+    let mut render = false;
+    let mut colour = true;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--parts" => rules.parts = args.next().ok_or("Missing symbol class")?.parse()?,
+            "--gears" => rules.gears = args.next().ok_or("Missing symbol class")?.parse()?,
+            "--neighbours" => {
+                rules.gear_neighbours = args.next().ok_or("Missing neighbour count")?.parse()?
             }
+            "--ratio" => rules.aggregation = args.next().ok_or("Missing aggregation")?.parse()?,
+            "--adjacency" => rules.adjacency = args.next().ok_or("Missing adjacency")?.parse()?,
+            "--render" => render = true,
+            "--no-color" => colour = false,
+            _ => return Err(format!("Unknown argument: {}", arg).into()),
         }
     }
 
-    // calc sum
-    let sum: u32 = relevant_numbers_set.iter().map(|x| x.value).sum();
-    println!("answer for part 1: {}", sum);
+    Ok(Options {
+        rules,
+        render,
+        colour,
+    })
+}
 
-    Ok(())
+struct Options {
+    rules: Rules,
+    render: bool,
+    colour: bool,
 }
 
-fn part2(input: &str) -> Result<(), Box<dyn Error>> {
-    let numbers_re = Regex::new(r"\d+").unwrap();
-    let gear_re = Regex::new(r"\*").unwrap();
-    let mut numbers_vec: Vec<Vec<Match>> = vec![];
-    let mut gears_vec: Vec<Vec<Match>> = vec![];
-    let mut sum: u32 = 0;
-
-    // find numbers and symbols in each line and put them in corresponding vector
-    input.lines().for_each(|line| {
-        let numbers: Vec<Match> = numbers_re.find_iter(line).collect();
-        numbers_vec.push(numbers);
-        let symbols: Vec<Match> = gear_re.find_iter(line).collect();
-        gears_vec.push(symbols);
-    });
-
-    // iterate over found symbols
-    for (gear_y, gear_line) in gears_vec.iter().enumerate() {
-        for gear in gear_line {
-            let gear_x = gear.start();
-            let mut touching_numbers_vec: Vec<u32> = vec![];
-
-            // iterate over numbers in relevant lines [y-1, y, y+1]
-            let start_y = gear_y.checked_sub(1).unwrap_or_default(); // not less than 0
-            let end_y = usize::min(numbers_vec.len() - 1, gear_y + 1); // not more than numbers_vec.len()
-
-            for number_line in &numbers_vec[start_y..=end_y] {
-                for number in number_line {
-                    // check if symbol is close to number in x-direction, y is already filtered to relevant only
-                    if gear_x >= number.start().checked_sub(1).unwrap_or_default()
-                        && gear_x <= number.end()
-                    // here no +1 because Match.end() uses exclusive end of range
-                    {
-                        touching_numbers_vec.push(number.as_str().parse().unwrap());
-                    }
-                }
-            }
+fn part1(schematic: &Schematic, rules: &Rules) -> Result<u64, String> {
+    // sum of all numbers next to a symbol
+    let sum = schematic
+        .part_numbers(&rules.parts)
+        .try_fold(0u64, |sum, number| sum.checked_add(number.value.into()))
+        .ok_or("Sum of the part numbers does not fit into u64")?;
+    println!("answer for part 1: {}", sum);
 
-            if touching_numbers_vec.len() == 2 {
-                sum += touching_numbers_vec.iter().product::<u32>();
-            }
-        }
-    }
+    Ok(sum)
+}
 
-    // print sum
+fn part2(schematic: &Schematic, rules: &Rules) -> Result<u64, String> {
+    // sum of the ratios of all gears
+    let sum = schematic
+        .gears(&rules.gears, rules.gear_neighbours)
+        .try_fold(0u64, |sum, gear| {
+            let values = schematic
+                .numbers_adjacent_to(gear)
+                .map(|number| number.value);
+            let symbol = &schematic.symbols[gear];
+            let ratio = rules.aggregation.apply(values).ok_or(format!(
+                "Row {}, column {}: gear ratio does not fit into u64",
+                symbol.y + 1,
+                symbol.x + 1
+            ))?;
+            sum.checked_add(ratio)
+                .ok_or("Sum of the gear ratios does not fit into u64".to_owned())
+        })?;
     println!("answer for part 2: {}", sum);
 
-    Ok(())
+    Ok(sum)
+}
+
+#[test]
+fn examples() {
+    use rules::{Adjacency, Aggregation, NeighbourCount, SymbolClass};
+
+    let example_input = "
+        467..114..
+        ...*......
+        ..35..633.
+        ......#...
+        617*......
+        .....+.58.
+        ..592.....
+        ......755.
+        ...$.*....
+        .664.598..
+    ";
+
+    let rules = Rules::default();
+    let schematic = Schematic::parse(example_input, rules.adjacency).unwrap();
+    assert_eq!(part1(&schematic, &rules), Ok(4361));
+    assert_eq!(part2(&schematic, &rules), Ok(467835));
+
+    let variant = Rules {
+        parts: SymbolClass::OneOf("*".to_owned()),
+        gear_neighbours: NeighbourCount::AtLeast(1),
+        aggregation: Aggregation::Max,
+        ..Rules::default()
+    };
+    assert_eq!(part1(&schematic, &variant), Ok(2472));
+    assert_eq!(part2(&schematic, &variant), Ok(467 + 617 + 755));
+
+    let orthogonal = Rules {
+        adjacency: Adjacency::Orthogonal,
+        ..Rules::default()
+    };
+    let schematic = Schematic::parse(example_input, orthogonal.adjacency).unwrap();
+    assert_eq!(
+        part1(&schematic, &orthogonal),
+        Ok(35 + 633 + 617 + 664 + 598)
+    );
+    assert_eq!(part2(&schematic, &orthogonal), Ok(0));
+
+    let radius = Rules {
+        adjacency: Adjacency::Radius(2),
+        ..Rules::default()
+    };
+    let schematic = Schematic::parse(example_input, radius.adjacency).unwrap();
+    assert_eq!(part1(&schematic, &radius), Ok(4361 + 114 + 58));
+
+    // the largest numbers still add up, but three of them don't multiply
+    let large = "
+        4294967295*4294967295
+        ..........4294967295.
+    ";
+    let schematic = Schematic::parse(large, rules.adjacency).unwrap();
+    assert_eq!(part1(&schematic, &rules), Ok(3 * u32::MAX as u64));
+    let three = Rules {
+        gear_neighbours: NeighbourCount::Exactly(3),
+        ..Rules::default()
+    };
+    assert_eq!(
+        part2(&schematic, &three),
+        Err("Row 1, column 11: gear ratio does not fit into u64".to_owned())
+    );
 }
@@ -0,0 +1,191 @@
+use crate::{rules::Rules, schematic::Schematic};
+use std::collections::HashSet;
+
+/// What a cell of the rendered schematic shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    PartNumber,
+    OtherNumber,
+    Gear,
+    /// Could be a gear, but touches a wrong number of numbers.
+    RejectedGear,
+    Symbol,
+}
+
+impl Style {
+    const ALL: [Style; 5] = [
+        Style::PartNumber,
+        Style::OtherNumber,
+        Style::Gear,
+        Style::RejectedGear,
+        Style::Symbol,
+    ];
+
+    fn ansi(&self) -> &'static str {
+        match self {
+            Style::PartNumber => "\x1b[32m",
+            Style::OtherNumber => "\x1b[90m",
+            Style::Gear => "\x1b[1;33m",
+            Style::RejectedGear => "\x1b[1;31m",
+            Style::Symbol => "\x1b[36m",
+        }
+    }
+
+    /// Stands in for the colour in the marker line below each row without colours.
+    fn marker(&self) -> char {
+        match self {
+            Style::PartNumber => 'P',
+            Style::OtherNumber => 'n',
+            Style::Gear => 'G',
+            Style::RejectedGear => 'x',
+            Style::Symbol => 's',
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Style::PartNumber => "part number",
+            Style::OtherNumber => "other number",
+            Style::Gear => "gear",
+            Style::RejectedGear => "gear symbol with wrong neighbour count",
+            Style::Symbol => "other symbol",
+        }
+    }
+
+    fn example(&self) -> &'static str {
+        match self {
+            Style::PartNumber | Style::OtherNumber => "123",
+            Style::Gear | Style::RejectedGear => "*",
+            Style::Symbol => "#",
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// The schematic with part numbers, other numbers, gears, rejected gears and other
+/// symbols told apart, followed by a legend.
+///
+/// With `colour` they are coloured with ANSI escape codes, otherwise every row is
+/// followed by a row of markers, one letter per cell.
+pub fn render(schematic: &Schematic, rules: &Rules, colour: bool) -> String {
+    let mut cells = vec![vec![('.', None); schematic.width]; schematic.height];
+
+    let parts: HashSet<(usize, usize)> = schematic
+        .part_numbers(&rules.parts)
+        .map(|number| (number.y, number.x_start))
+        .collect();
+
+    for number in &schematic.numbers {
+        let style = if parts.contains(&(number.y, number.x_start)) {
+            Style::PartNumber
+        } else {
+            Style::OtherNumber
+        };
+
+        for (x, digit) in (number.x_start..number.x_end).zip(number.digits.chars()) {
+            cells[number.y][x] = (digit, Some(style));
+        }
+    }
+
+    let gears: HashSet<usize> = schematic
+        .gears(&rules.gears, rules.gear_neighbours)
+        .collect();
+
+    for (s, symbol) in schematic.symbols.iter().enumerate() {
+        let style = if gears.contains(&s) {
+            Style::Gear
+        } else if rules.gears.contains(symbol.symbol) {
+            Style::RejectedGear
+        } else {
+            Style::Symbol
+        };
+
+        cells[symbol.y][symbol.x] = (symbol.symbol, Some(style));
+    }
+
+    let mut output = String::new();
+
+    for row in &cells {
+        if colour {
+            let mut current = None;
+            for &(c, style) in row {
+                if style != current {
+                    if current.is_some() {
+                        output.push_str(RESET);
+                    }
+                    if let Some(style) = style {
+                        output.push_str(style.ansi());
+                    }
+                    current = style;
+                }
+                output.push(c);
+            }
+            if current.is_some() {
+                output.push_str(RESET);
+            }
+            output.push('\n');
+        } else {
+            output.extend(row.iter().map(|(c, _)| c));
+            output.push('\n');
+            output.extend(
+                row.iter()
+                    .map(|(_, style)| style.map_or(' ', |s| s.marker())),
+            );
+            output.push('\n');
+        }
+    }
+
+    output.push_str("\nlegend:\n");
+    for style in Style::ALL {
+        let sample = if colour {
+            format!("{}{:>3}{}", style.ansi(), style.example(), RESET)
+        } else {
+            format!("{:>3}", style.marker())
+        };
+        output.push_str(&format!("  {} {}\n", sample, style.description()));
+    }
+
+    output
+}
+
+#[test]
+fn rendering() {
+    let schematic = Schematic::parse(
+        "
+        467..114..
+        ...*......
+        ..35......
+        ........*.
+        ",
+        Rules::default().adjacency,
+    )
+    .unwrap();
+
+    let plain = render(&schematic, &Rules::default(), false);
+    let mut lines = plain.lines();
+    assert_eq!(lines.next(), Some("467..114.."));
+    assert_eq!(lines.next(), Some("PPP  nnn  "));
+    assert_eq!(lines.next(), Some("...*......"));
+    assert_eq!(lines.next(), Some("   G      "));
+    assert_eq!(lines.next(), Some("..35......"));
+    assert_eq!(lines.next(), Some("  PP      "));
+    assert_eq!(lines.next(), Some("........*."));
+    assert_eq!(lines.next(), Some("        x "));
+    assert!(plain.contains("  x gear symbol with wrong neighbour count"));
+    assert!(!plain.contains('\x1b'));
+
+    let coloured = render(&schematic, &Rules::default(), true);
+    assert!(coloured.starts_with("\x1b[32m467\x1b[0m..\x1b[90m114\x1b[0m..\n"));
+    assert!(coloured.contains("\n...\x1b[1;33m*\x1b[0m......\n"));
+    assert!(coloured.contains("\n........\x1b[1;31m*\x1b[0m.\n"));
+
+    // leading zeros are shown as written
+    let schematic = Schematic::parse("007*..\n..0042", Rules::default().adjacency).unwrap();
+    let plain = render(&schematic, &Rules::default(), false);
+    let mut lines = plain.lines();
+    assert_eq!(lines.next(), Some("007*.."));
+    assert_eq!(lines.next(), Some("PPPG  "));
+    assert_eq!(lines.next(), Some("..0042"));
+    assert_eq!(lines.next(), Some("  PPPP"));
+}
@@ -0,0 +1,238 @@
+use crate::schematic::{Number, Symbol};
+use std::str::FromStr;
+
+/// Which symbols a rule looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolClass {
+    /// Every symbol, i.e. anything that is neither digit nor `.`.
+    Any,
+    OneOf(String),
+    NoneOf(String),
+}
+
+impl SymbolClass {
+    pub fn contains(&self, symbol: char) -> bool {
+        match self {
+            SymbolClass::Any => true,
+            SymbolClass::OneOf(symbols) => symbols.contains(symbol),
+            SymbolClass::NoneOf(symbols) => !symbols.contains(symbol),
+        }
+    }
+}
+
+impl FromStr for SymbolClass {
+    type Err = String;
+
+    /// `any`, `*#` for one of the listed symbols or `!*#` for all others.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "!" => Err("Empty symbol class".to_owned()),
+            "any" => Ok(SymbolClass::Any),
+            _ => Ok(match s.strip_prefix('!') {
+                Some(symbols) => SymbolClass::NoneOf(symbols.to_owned()),
+                None => SymbolClass::OneOf(s.to_owned()),
+            }),
+        }
+    }
+}
+
+/// How many numbers a gear has to touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeighbourCount {
+    Exactly(usize),
+    AtLeast(usize),
+    /// Both bounds inclusive.
+    Between(usize, usize),
+}
+
+impl NeighbourCount {
+    pub fn contains(&self, count: usize) -> bool {
+        match *self {
+            NeighbourCount::Exactly(n) => count == n,
+            NeighbourCount::AtLeast(n) => count >= n,
+            NeighbourCount::Between(min, max) => (min..=max).contains(&count),
+        }
+    }
+}
+
+impl FromStr for NeighbourCount {
+    type Err = String;
+
+    /// `2`, `2+` or `2-4`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| {
+            n.trim()
+                .parse()
+                .map_err(|_| format!("Invalid neighbour count `{}`", s))
+        };
+
+        if let Some(min) = s.strip_suffix('+') {
+            Ok(NeighbourCount::AtLeast(number(min)?))
+        } else if let Some((min, max)) = s.split_once('-') {
+            let (min, max) = (number(min)?, number(max)?);
+            if min > max {
+                return Err(format!("Empty neighbour count range `{}`", s));
+            }
+            Ok(NeighbourCount::Between(min, max))
+        } else {
+            Ok(NeighbourCount::Exactly(number(s)?))
+        }
+    }
+}
+
+/// How the numbers around a gear are combined into its ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    /// `None` if the ratio does not fit into u64.
+    pub fn apply(&self, values: impl Iterator<Item = u32>) -> Option<u64> {
+        let mut values = values.map(u64::from);
+        match self {
+            Aggregation::Product => {
+                values.try_fold(1u64, |product, value| product.checked_mul(value))
+            }
+            Aggregation::Sum => values.try_fold(0u64, |sum, value| sum.checked_add(value)),
+            Aggregation::Max => Some(values.max().unwrap_or(0)),
+        }
+    }
+}
+
+impl FromStr for Aggregation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Aggregation::Product),
+            "sum" => Ok(Aggregation::Sum),
+            "max" => Ok(Aggregation::Max),
+            _ => Err(format!("Unknown aggregation `{}`", s)),
+        }
+    }
+}
+
+/// When a symbol counts as touching a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Adjacency {
+    /// Next to one of the digits, also diagonally.
+    Diagonal,
+    /// Directly above, below, left or right of one of the digits.
+    Orthogonal,
+    /// At most `k` rows and columns away from one of the digits.
+    Radius(usize),
+}
+
+impl Adjacency {
+    /// Farthest a touching symbol can be away, in rows or columns.
+    pub fn reach(&self) -> usize {
+        match *self {
+            Adjacency::Diagonal | Adjacency::Orthogonal => 1,
+            Adjacency::Radius(k) => k,
+        }
+    }
+
+    pub fn touches(&self, symbol: &Symbol, number: &Number) -> bool {
+        let dy = symbol.y.abs_diff(number.y);
+        // distance to the closest digit, x_end is exclusive
+        let dx = if symbol.x < number.x_start {
+            number.x_start - symbol.x
+        } else {
+            (symbol.x + 1).saturating_sub(number.x_end)
+        };
+
+        match self {
+            Adjacency::Orthogonal => dy + dx == 1,
+            _ => dy <= self.reach() && dx <= self.reach(),
+        }
+    }
+}
+
+impl FromStr for Adjacency {
+    type Err = String;
+
+    /// `8`, `4` or `radius=k`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "8" => Ok(Adjacency::Diagonal),
+            "4" => Ok(Adjacency::Orthogonal),
+            _ => s
+                .strip_prefix("radius=")
+                .and_then(|k| k.parse().ok())
+                .map(Adjacency::Radius)
+                .ok_or(format!("Unknown adjacency `{}`", s)),
+        }
+    }
+}
+
+/// Everything that decides which numbers are part numbers and which symbols are gears.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub parts: SymbolClass,
+    pub gears: SymbolClass,
+    pub gear_neighbours: NeighbourCount,
+    pub aggregation: Aggregation,
+    pub adjacency: Adjacency,
+}
+
+impl Default for Rules {
+    /// The rules of the puzzle.
+    fn default() -> Self {
+        Rules {
+            parts: SymbolClass::Any,
+            gears: SymbolClass::OneOf("*".to_owned()),
+            gear_neighbours: NeighbourCount::Exactly(2),
+            aggregation: Aggregation::Product,
+            adjacency: Adjacency::Diagonal,
+        }
+    }
+}
+
+#[test]
+fn parse_rules() {
+    assert_eq!("any".parse(), Ok(SymbolClass::Any));
+    assert!("!*".parse::<SymbolClass>().unwrap().contains('#'));
+    assert!(!"*#".parse::<SymbolClass>().unwrap().contains('$'));
+    assert!("!".parse::<SymbolClass>().is_err());
+
+    assert_eq!("3".parse(), Ok(NeighbourCount::Exactly(3)));
+    assert_eq!("1+".parse(), Ok(NeighbourCount::AtLeast(1)));
+    assert_eq!("2-4".parse(), Ok(NeighbourCount::Between(2, 4)));
+    assert!("4-2".parse::<NeighbourCount>().is_err());
+    assert!("two".parse::<NeighbourCount>().is_err());
+
+    assert_eq!("radius=3".parse(), Ok(Adjacency::Radius(3)));
+    assert!("6".parse::<Adjacency>().is_err());
+    assert_eq!(Aggregation::Max.apply([3, 9, 4].into_iter()), Some(9));
+    assert_eq!(
+        Aggregation::Product.apply([100_000, 100_000].into_iter()),
+        Some(10_000_000_000)
+    );
+    assert_eq!(
+        Aggregation::Product.apply([u32::MAX, u32::MAX, 2].into_iter()),
+        None
+    );
+}
+
+#[test]
+fn adjacency_modes() {
+    let number = Number {
+        value: 123,
+        digits: "123".to_owned(),
+        y: 2,
+        x_start: 3,
+        x_end: 6,
+    };
+    let symbol = |y, x| Symbol { symbol: '*', y, x };
+
+    assert!(Adjacency::Diagonal.touches(&symbol(1, 2), &number));
+    assert!(!Adjacency::Orthogonal.touches(&symbol(1, 2), &number));
+    assert!(Adjacency::Orthogonal.touches(&symbol(1, 5), &number));
+    assert!(Adjacency::Orthogonal.touches(&symbol(2, 6), &number));
+    assert!(!Adjacency::Diagonal.touches(&symbol(2, 7), &number));
+    assert!(Adjacency::Radius(2).touches(&symbol(4, 7), &number));
+    assert!(!Adjacency::Radius(2).touches(&symbol(4, 8), &number));
+}
@@ -0,0 +1,209 @@
+use crate::rules::{Adjacency, NeighbourCount, SymbolClass};
+
+/// Number in the schematic, spanning the columns `x_start..x_end` of row `y`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    /// The digits as written, `value` loses leading zeros.
+    pub digits: String,
+    pub y: usize,
+    pub x_start: usize,
+    pub x_end: usize,
+}
+
+/// Anything that is neither digit nor `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub y: usize,
+    pub x: usize,
+}
+
+/// Numbers and symbols of an engine schematic, plus which of them touch each other
+/// under the chosen [`Adjacency`], stored as index lists in both directions.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    pub height: usize,
+    pub width: usize,
+    symbol_to_numbers: Vec<Vec<usize>>,
+    number_to_symbols: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    /// Surrounding whitespace of the input and its lines is ignored. Fails for
+    /// numbers that don't fit into a `u32`, naming the row and column they start at.
+    pub fn parse(input: &str, adjacency: Adjacency) -> Result<Self, String> {
+        let mut numbers: Vec<Number> = vec![];
+        let mut symbols: Vec<Symbol> = vec![];
+        let (mut height, mut width) = (0, 0);
+
+        // find numbers and symbols in each line
+        for (y, line) in input.trim().lines().enumerate() {
+            let line = line.trim();
+            height = y + 1;
+            width = usize::max(width, line.chars().count());
+            let mut current_number: Option<Number> = None;
+
+            for (x, c) in line.chars().enumerate() {
+                if let Some(digit) = c.to_digit(10) {
+                    let number = current_number.get_or_insert(Number {
+                        value: 0,
+                        digits: String::new(),
+                        y,
+                        x_start: x,
+                        x_end: x,
+                    });
+                    number.value = number
+                        .value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit))
+                        .ok_or_else(|| {
+                            let digits: String = line
+                                .chars()
+                                .skip(number.x_start)
+                                .take_while(char::is_ascii_digit)
+                                .collect();
+                            format!(
+                                "Row {}, column {}: number `{}` is too large",
+                                y + 1,
+                                number.x_start + 1,
+                                digits
+                            )
+                        })?;
+                    number.digits.push(c);
+                    number.x_end = x + 1;
+                    continue;
+                }
+
+                numbers.extend(current_number.take());
+
+                if c != '.' {
+                    symbols.push(Symbol { symbol: c, y, x });
+                }
+            }
+
+            numbers.extend(current_number);
+        }
+
+        // numbers per row, so each symbol only has to look at the rows within reach
+        let rows = numbers.last().map_or(0, |n| n.y + 1);
+        let mut numbers_by_row: Vec<Vec<usize>> = vec![vec![]; rows];
+        for (i, number) in numbers.iter().enumerate() {
+            numbers_by_row[number.y].push(i);
+        }
+
+        let mut symbol_to_numbers = vec![vec![]; symbols.len()];
+        let mut number_to_symbols = vec![vec![]; numbers.len()];
+
+        for (s, symbol) in symbols.iter().enumerate() {
+            let start_y = symbol.y.saturating_sub(adjacency.reach());
+            let end_y = usize::min(rows, symbol.y + adjacency.reach() + 1);
+
+            for &n in numbers_by_row
+                .get(start_y..end_y)
+                .unwrap_or_default()
+                .iter()
+                .flatten()
+            {
+                let number = &numbers[n];
+
+                if adjacency.touches(symbol, number) {
+                    symbol_to_numbers[s].push(n);
+                    number_to_symbols[n].push(s);
+                }
+            }
+        }
+
+        Ok(Schematic {
+            numbers,
+            symbols,
+            height,
+            width,
+            symbol_to_numbers,
+            number_to_symbols,
+        })
+    }
+
+    /// Numbers touching the symbol with index `symbol`.
+    pub fn numbers_adjacent_to(&self, symbol: usize) -> impl Iterator<Item = &Number> {
+        self.symbol_to_numbers[symbol]
+            .iter()
+            .map(|&n| &self.numbers[n])
+    }
+
+    /// Symbols touching the number with index `number`.
+    pub fn symbols_adjacent_to(&self, number: usize) -> impl Iterator<Item = &Symbol> {
+        self.number_to_symbols[number]
+            .iter()
+            .map(|&s| &self.symbols[s])
+    }
+
+    /// Numbers touching at least one symbol of `class`.
+    pub fn part_numbers<'a>(&'a self, class: &'a SymbolClass) -> impl Iterator<Item = &'a Number> {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(n, _)| {
+                self.symbols_adjacent_to(*n)
+                    .any(|symbol| class.contains(symbol.symbol))
+            })
+            .map(|(_, number)| number)
+    }
+
+    /// Indices of the symbols of `class` touching an allowed number of numbers.
+    pub fn gears<'a>(
+        &'a self,
+        class: &'a SymbolClass,
+        neighbours: NeighbourCount,
+    ) -> impl Iterator<Item = usize> + 'a {
+        (0..self.symbols.len()).filter(move |&s| {
+            class.contains(self.symbols[s].symbol)
+                && neighbours.contains(self.symbol_to_numbers[s].len())
+        })
+    }
+}
+
+#[test]
+fn adjacency() {
+    let schematic = Schematic::parse(
+        "
+        467..114..
+        ...*......
+        ..35..633.
+        12$.......
+        ",
+        Adjacency::Diagonal,
+    )
+    .unwrap();
+
+    let star = 0;
+    let values: Vec<u32> = schematic
+        .numbers_adjacent_to(star)
+        .map(|n| n.value)
+        .collect();
+    assert_eq!(values, vec![467, 35]);
+
+    let dollar = 1;
+    let values: Vec<u32> = schematic
+        .numbers_adjacent_to(dollar)
+        .map(|n| n.value)
+        .collect();
+    assert_eq!(values, vec![35, 12]);
+
+    let symbols: Vec<char> = schematic.symbols_adjacent_to(2).map(|s| s.symbol).collect();
+    assert_eq!(symbols, vec!['*', '$']);
+    assert_eq!(schematic.symbols_adjacent_to(1).count(), 0);
+    let stars = SymbolClass::OneOf("*".to_owned());
+    let gears: Vec<usize> = schematic
+        .gears(&stars, NeighbourCount::Exactly(2))
+        .collect();
+    assert_eq!(gears, vec![star]);
+
+    assert!(Schematic::parse("..4294967295", Adjacency::Diagonal).is_ok());
+    assert_eq!(
+        Schematic::parse("*.\n€.4294967296.", Adjacency::Diagonal).unwrap_err(),
+        "Row 2, column 3: number `4294967296` is too large"
+    );
+}
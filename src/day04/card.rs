@@ -0,0 +1,167 @@
+use advent_of_code_2023::parsing::{split_header, uints};
+use std::{collections::HashSet, error::Error, fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: usize,
+    pub winning: HashSet<u32>,
+    pub have: Vec<u32>,
+}
+
+impl Card {
+    /// How many of the numbers we have are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.have
+            .iter()
+            .filter(|number| self.winning.contains(number))
+            .count()
+    }
+
+    /// `2^(matches - 1)`, nothing without matches.
+    pub fn points(&self) -> Result<u64, PointsOverflow> {
+        match self.matches() {
+            0 => Ok(0),
+            count => u32::try_from(count - 1)
+                .ok()
+                .and_then(|shift| 1u64.checked_shl(shift))
+                .ok_or(PointsOverflow { card: self.id }),
+        }
+    }
+}
+
+/// The points of a card, or the sum of the points up to it, did not fit into u64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointsOverflow {
+    pub card: usize,
+}
+
+impl fmt::Display for PointsOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "points overflow at card {}", self.card)
+    }
+}
+
+impl Error for PointsOverflow {}
+
+impl FromStr for Card {
+    type Err = String;
+
+    /// `Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, numbers_str) = split_header(s).map_err(|_| "Missing `Card N:` label")?;
+
+        let id = label
+            .strip_prefix("Card")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or(format!("Invalid card label `{}`", label))?;
+
+        let (winning_str, have_str) = numbers_str
+            .split_once('|')
+            .ok_or("Missing `|` between the winning numbers and the numbers we have")?;
+
+        let winning = parse_numbers(winning_str)?;
+        let have = parse_numbers(have_str)?;
+
+        Ok(Card {
+            id,
+            winning: winning.into_iter().collect(),
+            have,
+        })
+    }
+}
+
+/// Space separated numbers, each may appear only once.
+fn parse_numbers(s: &str) -> Result<Vec<u32>, String> {
+    // `uints` skips anything between the numbers, here only spaces are allowed
+    if let Some(c) = s
+        .chars()
+        .find(|c| !c.is_ascii_digit() && !c.is_whitespace())
+    {
+        return Err(format!("Unexpected `{}` in `{}`", c, s.trim()));
+    }
+
+    let numbers: Vec<u32> = uints(s)
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = numbers.iter().find(|&&number| !seen.insert(number)) {
+        return Err(format!(
+            "Number {} appears twice in `{}`",
+            duplicate,
+            s.trim()
+        ));
+    }
+
+    Ok(numbers)
+}
+
+/// One card per line, numbered from 1 without gaps, errors mention the line number.
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, String> {
+    let mut cards: Vec<Card> = vec![];
+
+    for (line_nr, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let card: Card = line
+            .trim()
+            .parse()
+            .map_err(|e| format!("Line {}: {}", line_nr + 1, e))?;
+
+        // copies are won by position, so the ids have to match it
+        if card.id != cards.len() + 1 {
+            return Err(format!(
+                "Line {}: expected card {}, found card {}",
+                line_nr + 1,
+                cards.len() + 1,
+                card.id
+            ));
+        }
+
+        cards.push(card);
+    }
+
+    Ok(cards)
+}
+
+#[test]
+fn parse_errors() {
+    let card: Card = "Card 1: 41 48 300 | 300 86 6 48".parse().unwrap();
+    assert_eq!(card.id, 1);
+    assert_eq!(card.have, vec![300, 86, 6, 48]);
+    assert_eq!(card.matches(), 2);
+    assert_eq!(card.points(), Ok(2));
+
+    assert!("1: 41 | 41".parse::<Card>().is_err());
+    assert!("Card x: 41 | 41".parse::<Card>().is_err());
+    assert!("Card 1: 41 41".parse::<Card>().is_err());
+    assert!("Card 1: 41 a | 41".parse::<Card>().is_err());
+    assert!("Card 1: 41 | -41".parse::<Card>().is_err());
+    assert!("Card 1: 41 17 41 | 41".parse::<Card>().is_err());
+    assert!("Card 1: 41 | 17 17".parse::<Card>().is_err());
+
+    assert!("Card 1: 41 | 99999999999".parse::<Card>().is_err());
+
+    let err = parse_cards("Card 1: 1 | 1\nCard 3: 1 | 1").unwrap_err();
+    assert_eq!(err, "Line 2: expected card 2, found card 3");
+}
+
+#[test]
+fn points_overflow() {
+    let numbers = |count: u32| {
+        (1..=count)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let card = |count| -> Card {
+        format!("Card 7: {} | {}", numbers(count), numbers(count))
+            .parse()
+            .unwrap()
+    };
+
+    assert_eq!(card(64).points(), Ok(1 << 63));
+    assert_eq!(card(65).points(), Err(PointsOverflow { card: 7 }));
+}
@@ -1,89 +1,65 @@
+use card::{parse_cards, Card, PointsOverflow};
+use scratchcards::Scratchcards;
 use std::{error::Error, fs};
 
+mod card;
+mod scratchcards;
+
 fn main() -> Result<(), Box<dyn Error>> {
     let input: String = fs::read_to_string("src/day04/input")?.parse()?;
-    part1(&input)?;
-    part2(&input)?;
-
-    Ok(())
-}
-
-fn part1(input: &str) -> Result<(), Box<dyn Error>> {
-    let mut sum = 0;
+    let cards = parse_cards(&input)?;
 
-    for card in input.lines() {
-        // parse numbers from input
-        let all_numbers_str = card.split_once(':').unwrap().1;
-        let (winning_numbers_str, candidate_numbers_str) = all_numbers_str.split_once('|').unwrap();
-        let winning_numbers: Vec<u8> = winning_numbers_str
-            .split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect();
-        let candidate_numbers: Vec<u8> = candidate_numbers_str
-            .split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect();
-
-        // how many winning numbers are among the second set of numbers?
-        let count = candidate_numbers
-            .iter()
-            .filter(|x| winning_numbers.contains(x))
-            .count();
-
-        // calc points
-        let points = if count == 0 {
-            0
-        } else {
-            2i32.pow(count as u32 - 1)
-        };
-
-        // add points to overall sum
-        sum += points;
+    // `--provenance` prints where the copies of each card came from as CSV instead
+    match std::env::args().nth(1).as_deref() {
+        None => {
+            part1(&cards)?;
+            part2(&cards)?;
+        }
+        Some("--provenance") => {
+            let csv = Scratchcards::from_cards(&cards).provenance_csv::<u128>()?;
+            print!("{}", csv);
+        }
+        Some(arg) => return Err(format!("Unknown argument: {}", arg).into()),
     }
 
-    println!("The answer for part 1 is: {}", sum);
-
     Ok(())
 }
 
-fn part2(input: &str) -> Result<(), Box<dyn Error>> {
-    let mut instances: Vec<u32> = vec![1; input.lines().count()]; // one original card for each input line
-
-    for (card_nr, card) in input.lines().enumerate() {
-        // parse numbers from input
-        let all_numbers_str = card.split_once(':').unwrap().1;
-        let (winning_numbers_str, candidate_numbers_str) = all_numbers_str.split_once('|').unwrap();
-        let winning_numbers: Vec<u8> = winning_numbers_str
-            .split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect();
-        let candidate_numbers: Vec<u8> = candidate_numbers_str
-            .split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect();
-
-        // how many winning numbers are among the second set of numbers?
-        let count = candidate_numbers
-            .iter()
-            .filter(|x| winning_numbers.contains(x))
-            .count();
+fn part1(cards: &[Card]) -> Result<u64, PointsOverflow> {
+    let sum = cards.iter().try_fold(0u64, |sum, card| {
+        sum.checked_add(card.points()?)
+            .ok_or(PointsOverflow { card: card.id })
+    })?;
+    println!("The answer for part 1 is: {}", sum);
 
-        // which of the next cards will receive copies? + avoid index overflow
-        let end_index = usize::min(instances.len() - 1, card_nr + count);
+    Ok(sum)
+}
 
-        // how many copies will be added to the next cards?
-        let instances_current_card = instances[card_nr];
+fn part2(cards: &[Card]) -> Result<u128, Box<dyn Error>> {
+    let cards = Scratchcards::from_cards(cards);
 
-        // add copies of scratchcards
-        for index in (card_nr + 1)..=(end_index) {
-            instances[index] += instances_current_card;
-        }
-    }
+    // only count in u128 if u64 is not enough
+    let sum = match cards.total_copies::<u64>() {
+        Ok(sum) => sum as u128,
+        Err(_) => cards.total_copies::<u128>()?,
+    };
+    println!("The answer for part 2 is: {}", sum);
 
-    println!(
-        "The answer for part 2 is: {}",
-        instances.iter().sum::<u32>()
-    );
+    Ok(sum)
+}
 
-    Ok(())
+#[test]
+fn examples() {
+    let example_input = "
+        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 99 20
+        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+    ";
+
+    let cards = parse_cards(example_input).unwrap();
+    assert_eq!(part1(&cards), Ok(13));
+    assert_eq!(part2(&cards).unwrap(), 30);
 }
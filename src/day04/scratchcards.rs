@@ -0,0 +1,163 @@
+use crate::card::Card;
+use std::{error::Error, fmt};
+
+/// Unsigned integer type copy counts are computed in.
+pub trait CopyCount: Copy + Default + fmt::Display {
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_copy_count {
+    ($($t:ty),*) => {$(
+        impl CopyCount for $t {
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+        }
+    )*};
+}
+
+impl_copy_count!(u32, u64, u128);
+
+/// A copy count did not fit into the chosen integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyOverflow {
+    /// The card whose copies, or the running total up to it, overflowed.
+    pub card: usize,
+}
+
+impl fmt::Display for CopyOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "copy count overflows at card {}", self.card)
+    }
+}
+
+impl Error for CopyOverflow {}
+
+/// Copies of one card and where they came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance<T> {
+    pub card: usize,
+    /// All copies, including the original.
+    pub total: T,
+    /// (earlier card, copies of this card won by it), the original is not included.
+    pub won_from: Vec<(usize, T)>,
+}
+
+/// The pile of scratchcards, card `i` (counted from 1) is at index `i - 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scratchcards {
+    matches: Vec<usize>,
+}
+
+impl Scratchcards {
+    pub fn from_cards(cards: &[Card]) -> Self {
+        Self::from_matches(cards.iter().map(Card::matches).collect())
+    }
+
+    /// Cards with the given numbers of matching numbers.
+    pub fn from_matches(matches: Vec<usize>) -> Self {
+        Scratchcards { matches }
+    }
+
+    /// Copies of each card after all winnings are handed out, including the original.
+    pub fn copies<T: CopyCount>(&self) -> Result<Vec<T>, CopyOverflow> {
+        let mut copies = vec![T::ONE; self.matches.len()];
+
+        for (i, &count) in self.matches.iter().enumerate() {
+            // cards past the end of the pile are not won
+            let end = usize::min(copies.len(), i + 1 + count);
+
+            for j in i + 1..end {
+                copies[j] = copies[j]
+                    .checked_add(copies[i])
+                    .ok_or(CopyOverflow { card: j + 1 })?;
+            }
+        }
+
+        Ok(copies)
+    }
+
+    /// Total number of cards after all winnings are handed out.
+    pub fn total_copies<T: CopyCount>(&self) -> Result<T, CopyOverflow> {
+        self.copies::<T>()?
+            .into_iter()
+            .enumerate()
+            .try_fold(T::default(), |sum, (i, copies)| {
+                sum.checked_add(copies).ok_or(CopyOverflow { card: i + 1 })
+            })
+    }
+
+    /// Copies of each card broken down by the cards that won them.
+    pub fn provenance<T: CopyCount>(&self) -> Result<Vec<Provenance<T>>, CopyOverflow> {
+        let copies = self.copies::<T>()?;
+        let mut provenance: Vec<Provenance<T>> = copies
+            .iter()
+            .enumerate()
+            .map(|(i, &total)| Provenance {
+                card: i + 1,
+                total,
+                won_from: vec![],
+            })
+            .collect();
+
+        // every copy of card i wins one copy of each of the next `matches[i]` cards
+        for (i, &count) in self.matches.iter().enumerate() {
+            let end = usize::min(copies.len(), i + 1 + count);
+            for entry in &mut provenance[i + 1..end] {
+                entry.won_from.push((i + 1, copies[i]));
+            }
+        }
+
+        Ok(provenance)
+    }
+
+    /// The provenance as CSV with the columns `card,source,copies`, where source is
+    /// `original` or the number of the card that won the copies.
+    pub fn provenance_csv<T: CopyCount>(&self) -> Result<String, CopyOverflow> {
+        let mut csv = "card,source,copies\n".to_owned();
+
+        for entry in self.provenance::<T>()? {
+            csv.push_str(&format!("{},original,1\n", entry.card));
+            for (source, copies) in entry.won_from {
+                csv.push_str(&format!("{},{},{}\n", entry.card, source, copies));
+            }
+        }
+
+        Ok(csv)
+    }
+}
+
+#[test]
+fn copies_and_provenance() {
+    let cards = Scratchcards::from_matches(vec![4, 2, 2, 1, 0, 0]);
+
+    assert_eq!(cards.copies::<u64>(), Ok(vec![1, 2, 4, 8, 14, 1]));
+    assert_eq!(cards.total_copies::<u32>(), Ok(30));
+
+    let provenance = cards.provenance::<u64>().unwrap();
+    assert_eq!(provenance[0].won_from, vec![]);
+    assert_eq!(provenance[4].total, 14);
+    assert_eq!(provenance[4].won_from, vec![(1, 1), (3, 4), (4, 8)]);
+
+    let csv = cards.provenance_csv::<u64>().unwrap();
+    assert!(csv.starts_with("card,source,copies\n1,original,1\n2,original,1\n2,1,1\n"));
+    assert!(csv.ends_with("5,4,8\n6,original,1\n"));
+}
+
+#[test]
+fn copy_overflow() {
+    // every card wins a copy of all later cards, so card k has 2^(k-1) copies
+    let cards = Scratchcards::from_matches((0..70).rev().collect());
+
+    assert_eq!(cards.copies::<u64>(), Err(CopyOverflow { card: 65 }));
+    assert_eq!(cards.total_copies::<u128>(), Ok((1 << 70) - 1));
+
+    // the copies fit, but not their sum
+    let cards = Scratchcards::from_matches((0..64).rev().chain([0]).collect());
+    assert!(cards.copies::<u64>().is_ok());
+    assert_eq!(cards.total_copies::<u64>(), Err(CopyOverflow { card: 65 }));
+}
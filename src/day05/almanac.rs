@@ -0,0 +1,514 @@
+use crate::piecewise::Piecewise;
+use advent_of_code_2023::{
+    graph::{bfs, shortest_path},
+    parsing::{split_header, uints},
+};
+use std::{cmp::Ordering, collections::HashMap, fmt, ops::Range};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingRange {
+    pub source_range: Range<u64>,
+    pub dest_start: u64,
+}
+
+impl PartialOrd for MappingRange {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MappingRange {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.source_range.start.cmp(&other.source_range.start)
+    }
+}
+
+/// One `source-to-destination map:` block, the ranges sorted by source start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryMap {
+    pub source: String,
+    pub destination: String,
+    pub ranges: Vec<MappingRange>,
+}
+
+impl CategoryMap {
+    /// The range containing `value`, the first one if they overlap.
+    pub fn lookup(&self, value: u64) -> Option<&MappingRange> {
+        self.ranges
+            .iter()
+            .take_while(|range| range.source_range.start <= value)
+            .find(|range| range.source_range.contains(&value))
+    }
+
+    /// Numbers outside of all ranges stay unchanged.
+    pub fn apply(&self, value: u64) -> u64 {
+        self.lookup(value).map_or(value, |range| {
+            range.dest_start + (value - range.source_range.start)
+        })
+    }
+
+    /// Overlaps and gaps between the ranges, ordered by the start of the later range.
+    pub fn issues(&self) -> Vec<RangeIssue> {
+        let mut issues = vec![];
+
+        // the range reaching furthest so far, the ranges are sorted by source start
+        let mut furthest: Option<&Range<u64>> = None;
+        for range in &self.ranges {
+            let source = &range.source_range;
+            match furthest {
+                Some(previous) if source.start < previous.end => {
+                    issues.push(RangeIssue::SourceOverlap(previous.clone(), source.clone()))
+                }
+                Some(previous) if source.start > previous.end => {
+                    issues.push(RangeIssue::Gap(previous.end..source.start))
+                }
+                _ => {}
+            }
+            if furthest.is_none_or(|previous| source.end > previous.end) {
+                furthest = Some(source);
+            }
+        }
+
+        let mut destinations: Vec<Range<u64>> = self
+            .ranges
+            .iter()
+            .map(|range| {
+                let len = range.source_range.end - range.source_range.start;
+                range.dest_start..range.dest_start + len
+            })
+            .collect();
+        destinations.sort_by_key(|dest| dest.start);
+
+        let mut furthest: Option<&Range<u64>> = None;
+        for dest in &destinations {
+            if let Some(previous) = furthest.filter(|previous| dest.start < previous.end) {
+                issues.push(RangeIssue::DestinationOverlap(
+                    previous.clone(),
+                    dest.clone(),
+                ));
+            }
+            if furthest.is_none_or(|previous| dest.end > previous.end) {
+                furthest = Some(dest);
+            }
+        }
+
+        issues
+    }
+}
+
+/// Something unusual about the ranges of a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeIssue {
+    /// Two ranges share source numbers, the one starting first is used for them.
+    SourceOverlap(Range<u64>, Range<u64>),
+    /// Two ranges share destination numbers, so the map can't be inverted.
+    DestinationOverlap(Range<u64>, Range<u64>),
+    /// Source numbers between two ranges, they stay unchanged.
+    Gap(Range<u64>),
+}
+
+impl fmt::Display for RangeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeIssue::SourceOverlap(a, b) => {
+                write!(f, "sources {:?} and {:?} overlap", a, b)
+            }
+            RangeIssue::DestinationOverlap(a, b) => {
+                write!(f, "destinations {:?} and {:?} overlap", a, b)
+            }
+            RangeIssue::Gap(gap) => write!(f, "gap {:?} is not mapped", gap),
+        }
+    }
+}
+
+/// What one map did to a value, see [`Almanac::trace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep<'a> {
+    pub map: &'a CategoryMap,
+    pub value: u64,
+    /// `None` if the value was in a gap between the ranges and stayed unchanged.
+    pub range: Option<&'a MappingRange>,
+    /// Added to the value, `result - value`.
+    pub offset: i128,
+    pub result: u64,
+}
+
+/// The seeds and all maps of the input, with the category names of their headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    pub maps: Vec<CategoryMap>,
+}
+
+impl Almanac {
+    /// Fails on malformed headers, seeds or ranges, on a second `seeds:` line, on ranges
+    /// whose source or destination goes past `u64::MAX`, on two maps between the same
+    /// categories, on maps that can't be reached from `seed` and if there are maps but
+    /// none lead to `location`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut seeds = None;
+        let mut maps: Vec<CategoryMap> = vec![];
+
+        // line by line, so errors can name the line, offsets of number errors are into it
+        for (line_nr, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let error = |message: String| format!("Line {}: {}", line_nr + 1, message);
+            // `uints` skips anything between the numbers, in `s` only spaces are allowed
+            let numbers = |s: &str| {
+                if let Some(c) = s
+                    .chars()
+                    .find(|c| !c.is_ascii_digit() && !c.is_whitespace())
+                {
+                    return Err(error(format!("unexpected `{}` in `{}`", c, line.trim())));
+                }
+
+                uints(line)
+                    .collect::<Result<Vec<u64>, _>>()
+                    .map_err(|e| error(e.to_string()))
+            };
+
+            match split_header(line) {
+                Ok(("seeds", _)) if seeds.is_some() => {
+                    return Err(error("second `seeds:` line".to_owned()))
+                }
+                Ok(("seeds", data)) => seeds = Some(numbers(data)?),
+                // `x-to-y map:` starts a new map, the ranges follow on the next lines
+                Ok((header, data)) => {
+                    let (source, destination) = header
+                        .strip_suffix(" map")
+                        .and_then(|categories| categories.split_once("-to-"))
+                        .filter(|_| data.is_empty())
+                        .ok_or(error(format!(
+                            "expected `x-to-y map:`, found `{}`",
+                            line.trim()
+                        )))?;
+
+                    if maps
+                        .iter()
+                        .any(|map| map.source == source && map.destination == destination)
+                    {
+                        return Err(error(format!(
+                            "two maps from {} to {}",
+                            source, destination
+                        )));
+                    }
+
+                    maps.push(CategoryMap {
+                        source: source.to_owned(),
+                        destination: destination.to_owned(),
+                        ranges: vec![],
+                    });
+                }
+                Err(_) => {
+                    let map = maps.last_mut().ok_or(error(format!(
+                        "range `{}` is not part of a map",
+                        line.trim()
+                    )))?;
+
+                    let [dest_start, source_start, range_len] = numbers(line)?[..] else {
+                        return Err(error(format!("expected 3 numbers in `{}`", line.trim())));
+                    };
+
+                    let (Some(source_end), Some(_)) = (
+                        source_start.checked_add(range_len),
+                        dest_start.checked_add(range_len),
+                    ) else {
+                        return Err(error(format!("range `{}` goes past u64::MAX", line.trim())));
+                    };
+
+                    map.ranges.push(MappingRange {
+                        source_range: source_start..source_end,
+                        dest_start,
+                    });
+                }
+            }
+        }
+
+        let seeds = seeds.ok_or("Missing `seeds:` line")?;
+        for map in &mut maps {
+            map.ranges.sort();
+        }
+
+        let almanac = Almanac { seeds, maps };
+
+        // every map has to be reachable from the seeds, and so has the location
+        let reachable = bfs(&almanac.category_graph(), "seed");
+        if let Some(map) = almanac
+            .maps
+            .iter()
+            .find(|map| !reachable.contains_key(map.source.as_str()))
+        {
+            return Err(format!(
+                "Map from {} to {} is not connected, no maps lead from seed to {}",
+                map.source, map.destination, map.source
+            ));
+        }
+        if !almanac.maps.is_empty() && !reachable.contains_key("location") {
+            return Err("No maps lead from seed to location".to_owned());
+        }
+
+        Ok(almanac)
+    }
+
+    /// The seeds read as pairs of range start and length, as in part 2.
+    pub fn seed_ranges(&self) -> Result<Vec<Range<u64>>, String> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(format!(
+                "Expected pairs of seed start and length, found {} numbers",
+                self.seeds.len()
+            ));
+        }
+
+        self.seeds
+            .chunks(2)
+            .map(|pair| {
+                let (start, len) = (pair[0], pair[1]);
+                let end = start
+                    .checked_add(len)
+                    .ok_or(format!("Seed range {} {} goes past u64::MAX", start, len))?;
+                Ok(start..end)
+            })
+            .collect()
+    }
+
+    /// The maps to apply one after the other to get from category `from` to `to`,
+    /// taking the fewest maps if there are several ways.
+    pub fn route(&self, from: &str, to: &str) -> Result<Vec<&CategoryMap>, String> {
+        let (_, categories) =
+            shortest_path(&self.category_graph(), from, |&category| category == to)
+                .ok_or(format!("No maps lead from {} to {}", from, to))?;
+
+        Ok(categories
+            .windows(2)
+            .map(|pair| {
+                self.maps
+                    .iter()
+                    .find(|map| map.source == pair[0] && map.destination == pair[1])
+                    .expect("every edge of the graph is a map")
+            })
+            .collect())
+    }
+
+    /// Edges from the source to the destination category of every map.
+    fn category_graph(&self) -> HashMap<&str, Vec<&str>> {
+        let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+        for map in &self.maps {
+            graph
+                .entry(map.source.as_str())
+                .or_default()
+                .push(map.destination.as_str());
+        }
+        graph
+    }
+
+    /// All maps from category `from` to `to` combined into a single function.
+    pub fn compose(&self, from: &str, to: &str) -> Result<Piecewise, String> {
+        Ok(self
+            .route(from, to)?
+            .into_iter()
+            .fold(Piecewise::identity(), |composed, map| {
+                composed.then(&Piecewise::from_map(map))
+            }))
+    }
+
+    /// The value after each map on the way from seed to location.
+    pub fn trace(&self, seed: u64) -> Result<Vec<TraceStep<'_>>, String> {
+        let mut value = seed;
+        let mut steps = vec![];
+
+        for map in self.route("seed", "location")? {
+            let result = map.apply(value);
+            steps.push(TraceStep {
+                map,
+                value,
+                range: map.lookup(value),
+                offset: result as i128 - value as i128,
+                result,
+            });
+            value = result;
+        }
+
+        Ok(steps)
+    }
+
+    /// Value of category `to` that corresponds to `value` of category `from`.
+    pub fn convert(&self, value: u64, from: &str, to: &str) -> Result<u64, String> {
+        Ok(self
+            .route(from, to)?
+            .iter()
+            .fold(value, |value, map| map.apply(value)))
+    }
+}
+
+#[test]
+fn routing() {
+    let almanac = Almanac::parse(
+        "seeds: 1 2
+
+        seed-to-soil map:
+        10 0 5
+
+        soil-to-water map:
+        100 10 5
+
+        seed-to-light map:
+        50 0 3
+
+        light-to-temperature map:
+        7 50 1
+
+        temperature-to-water map:
+        0 7 1
+
+        water-to-location map:
+        1000 100 10
+        500 0 1",
+    )
+    .unwrap();
+
+    assert_eq!(almanac.convert(1, "seed", "soil"), Ok(11));
+    // seed-soil-water is shorter than seed-light-temperature-water
+    assert_eq!(almanac.convert(1, "seed", "water"), Ok(101));
+    assert_eq!(almanac.convert(4, "seed", "location"), Ok(1004));
+    assert_eq!(almanac.convert(50, "light", "location"), Ok(500));
+    assert_eq!(almanac.convert(7, "soil", "soil"), Ok(7));
+    assert!(almanac.convert(1, "water", "seed").is_err());
+
+    let route: Vec<&str> = almanac
+        .route("seed", "location")
+        .unwrap()
+        .iter()
+        .map(|map| map.destination.as_str())
+        .collect();
+    assert_eq!(route, vec!["soil", "water", "location"]);
+
+    assert!(
+        Almanac::parse("seeds: 1\n\nseed-to-soil map:\n1 2 3\n\nseed-to-soil map:\n4 5 6").is_err()
+    );
+    assert!(Almanac::parse("seeds: 1\n\nsoil-to-water map:\n1 2 3").is_err());
+    assert!(Almanac::parse("seeds: 1\n\nseed-soil map:\n1 2 3").is_err());
+    // the maps lead into each other, but not from the seeds
+    assert_eq!(
+        Almanac::parse(
+            "seeds: 1\n\nseed-to-location map:\n1 2 3\n\nfoo-to-bar map:\n1 2 3\n\nbar-to-foo map:\n1 2 3"
+        ),
+        Err("Map from foo to bar is not connected, no maps lead from seed to foo".to_owned())
+    );
+    assert_eq!(
+        Almanac::parse("seeds: 1\n\nseed-to-soil map:\n1 2 3"),
+        Err("No maps lead from seed to location".to_owned())
+    );
+    assert!(Almanac::parse("seeds: 1\n\nseed-to-soil map:\n1 2").is_err());
+
+    let max = u64::MAX;
+    let range =
+        |line: String| Almanac::parse(&format!("seeds: 1\n\nseed-to-location map:\n{}", line));
+    assert!(range(format!("0 {} 1", max - 1)).is_ok());
+    assert!(range(format!("{} 0 1", max - 1)).is_ok());
+    assert_eq!(
+        range(format!("0 {} 2", max - 1)),
+        Err(format!(
+            "Line 4: range `0 {} 2` goes past u64::MAX",
+            max - 1
+        ))
+    );
+    // number errors name the line, the offset is into it
+    assert_eq!(
+        range("1 2 99999999999999999999".to_owned()),
+        Err("Line 4: invalid number `99999999999999999999` at offset 4".to_owned())
+    );
+    assert_eq!(
+        range("-50 x98 y2".to_owned()),
+        Err("Line 4: unexpected `-` in `-50 x98 y2`".to_owned())
+    );
+    assert_eq!(
+        Almanac::parse("seeds: 1, 2"),
+        Err("Line 1: unexpected `,` in `seeds: 1, 2`".to_owned())
+    );
+    assert_eq!(
+        Almanac::parse("seeds: 1\nseeds: 2"),
+        Err("Line 2: second `seeds:` line".to_owned())
+    );
+    assert_eq!(
+        Almanac::parse("seed-to-location map:\n1 2 3"),
+        Err("Missing `seeds:` line".to_owned())
+    );
+    assert!(range(format!("{} 0 2", max - 1)).is_err());
+
+    let seeds = |seeds: &str| Almanac::parse(&format!("seeds: {}", seeds)).unwrap();
+    assert!(seeds("79 14 55").seed_ranges().is_err());
+    assert_eq!(
+        seeds(&format!("{} 1 7 0", u64::MAX - 1)).seed_ranges(),
+        Ok(vec![u64::MAX - 1..u64::MAX, 7..7])
+    );
+    assert_eq!(
+        seeds(&format!("{} 2", u64::MAX - 1)).seed_ranges(),
+        Err(format!("Seed range {} 2 goes past u64::MAX", u64::MAX - 1))
+    );
+
+    let composed = almanac.compose("seed", "location").unwrap();
+    for seed in 0..20 {
+        assert_eq!(
+            Ok(composed.apply(seed)),
+            almanac.convert(seed, "seed", "location")
+        );
+    }
+}
+
+#[test]
+fn range_issues() {
+    let almanac = Almanac::parse(
+        "seeds: 1
+
+        seed-to-location map:
+        100 0 10
+        200 5 10
+        105 20 5",
+    )
+    .unwrap();
+
+    assert_eq!(
+        almanac.maps[0].issues(),
+        vec![
+            RangeIssue::SourceOverlap(0..10, 5..15),
+            RangeIssue::Gap(15..20),
+            RangeIssue::DestinationOverlap(100..110, 105..110),
+        ]
+    );
+}
+
+#[test]
+fn tracing() {
+    let almanac = Almanac::parse(
+        "seeds: 79
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-location map:
+        0 15 37",
+    )
+    .unwrap();
+
+    let steps = almanac.trace(79).unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(
+        (steps[0].value, steps[0].result, steps[0].offset),
+        (79, 81, 2)
+    );
+    assert_eq!(steps[0].range.unwrap().source_range, 50..98);
+    assert_eq!(steps[1].map.destination, "location");
+    assert_eq!(
+        (steps[1].value, steps[1].result, steps[1].offset),
+        (81, 81, 0)
+    );
+    assert_eq!(steps[1].range, None);
+
+    let steps = almanac.trace(98).unwrap();
+    assert_eq!(steps[0].offset, -48);
+    assert_eq!(steps[1].result, 35);
+}
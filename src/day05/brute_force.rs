@@ -0,0 +1,338 @@
+use crate::almanac::{Almanac, CategoryMap};
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fs,
+    ops::Range,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub threads: usize,
+    /// Seeds per unit of work, also the granularity of the checkpoint.
+    pub chunk_size: u64,
+    /// Finished chunks are recorded here and skipped when starting again.
+    pub checkpoint: Option<PathBuf>,
+    /// Print progress and ETA to stderr about once per second.
+    pub progress: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            chunk_size: 10_000_000,
+            checkpoint: None,
+            progress: false,
+        }
+    }
+}
+
+/// Progress of a run, as stored in the checkpoint file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Checkpoint {
+    /// [`fingerprint`] of the almanac the run is for.
+    almanac: u64,
+    chunk_size: u64,
+    chunks: usize,
+    best: Option<u64>,
+    done: BTreeSet<usize>,
+}
+
+impl Checkpoint {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut checkpoint = Checkpoint {
+            almanac: 0,
+            chunk_size: 0,
+            chunks: 0,
+            best: None,
+            done: BTreeSet::new(),
+        };
+
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let number = |s: &str| {
+                s.parse()
+                    .map_err(|_| format!("Invalid number `{}` in checkpoint", s))
+            };
+
+            match key {
+                "almanac" => {
+                    checkpoint.almanac = u64::from_str_radix(value, 16)
+                        .map_err(|_| format!("Invalid almanac fingerprint `{}`", value))?
+                }
+                "chunk_size" => checkpoint.chunk_size = number(value)?,
+                "chunks" => checkpoint.chunks = number(value)? as usize,
+                "best" if value == "none" => checkpoint.best = None,
+                "best" => checkpoint.best = Some(number(value)?),
+                "done" => {
+                    for chunk in value.split_whitespace() {
+                        checkpoint.done.insert(number(chunk)? as usize);
+                    }
+                }
+                _ => return Err(format!("Unknown checkpoint line `{}`", line)),
+            }
+        }
+
+        Ok(checkpoint)
+    }
+
+    fn write(&self, path: &PathBuf) -> Result<(), Box<dyn Error>> {
+        let done: Vec<String> = self.done.iter().map(|chunk| chunk.to_string()).collect();
+        let text = format!(
+            "# day05 brute force checkpoint\nalmanac {:016x}\nchunk_size {}\nchunks {}\nbest {}\ndone {}\n",
+            self.almanac,
+            self.chunk_size,
+            self.chunks,
+            self.best.map_or("none".to_owned(), |best| best.to_string()),
+            done.join(" ")
+        );
+
+        // write to a temporary file first, so an interrupted write can't lose progress
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(tmp, path)?;
+
+        Ok(())
+    }
+}
+
+/// Lowest location of all seeds in the seed ranges, trying every single seed.
+///
+/// The seed ranges are cut into chunks that the threads take one after the other,
+/// only the best location per chunk is kept, so memory doesn't grow with the seeds.
+pub fn brute_force(almanac: &Almanac, options: &Options) -> Result<u64, Box<dyn Error>> {
+    let route = almanac.route("seed", "location")?;
+    if options.chunk_size == 0 {
+        return Err("Chunk size must be at least 1".into());
+    }
+
+    let mut chunks: Vec<Range<u64>> = vec![];
+    for seed_range in almanac.seed_ranges()? {
+        let end = seed_range.end;
+
+        for chunk_start in seed_range.step_by(options.chunk_size as usize) {
+            let chunk_end = chunk_start
+                .checked_add(options.chunk_size)
+                .map_or(end, |chunk_end| u64::min(end, chunk_end));
+            chunks.push(chunk_start..chunk_end);
+        }
+    }
+
+    let almanac_hash = fingerprint(almanac);
+    let mut checkpoint = match &options.checkpoint {
+        Some(path) if path.exists() => Checkpoint::parse(&fs::read_to_string(path)?)?,
+        _ => Checkpoint {
+            almanac: almanac_hash,
+            chunk_size: options.chunk_size,
+            chunks: chunks.len(),
+            best: None,
+            done: BTreeSet::new(),
+        },
+    };
+
+    // the chunks and best location only mean something for the same seeds and maps
+    if checkpoint.almanac != almanac_hash {
+        return Err(format!(
+            "Checkpoint is for another almanac ({:016x}, this one is {:016x})",
+            checkpoint.almanac, almanac_hash
+        )
+        .into());
+    }
+
+    if (checkpoint.chunk_size, checkpoint.chunks) != (options.chunk_size, chunks.len()) {
+        return Err(format!(
+            "Checkpoint is for {} chunks of {} seeds, not {} of {}",
+            checkpoint.chunks,
+            checkpoint.chunk_size,
+            chunks.len(),
+            options.chunk_size
+        )
+        .into());
+    }
+
+    let pending: Vec<usize> = (0..chunks.len())
+        .filter(|chunk| !checkpoint.done.contains(chunk))
+        .collect();
+    let pending_seeds: u64 = pending
+        .iter()
+        .map(|&i| chunks[i].end - chunks[i].start)
+        .sum();
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| -> Result<(), Box<dyn Error>> {
+        for _ in 0..options.threads.max(1) {
+            let sender = sender.clone();
+            let (next, pending, chunks, route) = (&next, &pending, &chunks, &route);
+
+            scope.spawn(move || {
+                while let Some(&chunk) = pending.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let best = chunks[chunk]
+                        .clone()
+                        .map(|seed| location(route, seed))
+                        .min();
+                    if sender.send((chunk, best)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let start = Instant::now();
+        let mut last_report = start;
+        let mut seeds_done = 0;
+
+        for (chunk, best) in receiver {
+            checkpoint.best = checkpoint.best.into_iter().chain(best).min();
+            checkpoint.done.insert(chunk);
+            seeds_done += chunks[chunk].end - chunks[chunk].start;
+
+            if let Some(path) = &options.checkpoint {
+                checkpoint.write(path)?;
+            }
+
+            if options.progress && last_report.elapsed() >= Duration::from_secs(1) {
+                last_report = Instant::now();
+                let elapsed = start.elapsed().as_secs_f64();
+                let eta = elapsed / seeds_done as f64 * (pending_seeds - seeds_done) as f64;
+                eprintln!(
+                    "{}/{} chunks, {:.1}% of the remaining seeds, best so far {}, ETA {:.0}s",
+                    checkpoint.done.len(),
+                    chunks.len(),
+                    100.0 * seeds_done as f64 / pending_seeds as f64,
+                    checkpoint
+                        .best
+                        .map_or("-".to_owned(), |best| best.to_string()),
+                    eta
+                );
+            }
+        }
+
+        Ok(())
+    })?;
+
+    Ok(checkpoint.best.ok_or("No seeds")?)
+}
+
+/// FNV-1a hash of the seeds and all maps, fed with explicit little-endian bytes so
+/// it doesn't depend on the Rust version or platform and checkpoints stay usable.
+fn fingerprint(almanac: &Almanac) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut write = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    };
+
+    // lengths first, so that different splits of the same bytes differ
+    write(&(almanac.seeds.len() as u64).to_le_bytes());
+    for seed in &almanac.seeds {
+        write(&seed.to_le_bytes());
+    }
+
+    write(&(almanac.maps.len() as u64).to_le_bytes());
+    for map in &almanac.maps {
+        for name in [&map.source, &map.destination] {
+            write(&(name.len() as u64).to_le_bytes());
+            write(name.as_bytes());
+        }
+
+        write(&(map.ranges.len() as u64).to_le_bytes());
+        for range in &map.ranges {
+            let len = range.source_range.end - range.source_range.start;
+            for number in [range.dest_start, range.source_range.start, len] {
+                write(&number.to_le_bytes());
+            }
+        }
+    }
+
+    hash
+}
+
+fn location(route: &[&CategoryMap], seed: u64) -> u64 {
+    route.iter().fold(seed, |value, map| map.apply(value))
+}
+
+#[test]
+fn checkpoints() {
+    let almanac = Almanac::parse(
+        "seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-location map:
+        0 15 37",
+    )
+    .unwrap();
+
+    let path = std::env::temp_dir().join(format!("day05-checkpoint-{}", std::process::id()));
+    let options = Options {
+        threads: 3,
+        chunk_size: 4,
+        checkpoint: Some(path.clone()),
+        progress: false,
+    };
+
+    assert_eq!(brute_force(&almanac, &options).unwrap(), 57);
+    let checkpoint = Checkpoint::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(checkpoint.chunks, 8);
+    assert_eq!(checkpoint.done.len(), 8);
+    assert_eq!(checkpoint.best, Some(57));
+
+    // chunks recorded as done are not looked at again
+    let resumed = Checkpoint {
+        best: Some(3),
+        done: (0..7).collect(),
+        ..checkpoint
+    };
+    resumed.write(&path).unwrap();
+    assert_eq!(brute_force(&almanac, &options).unwrap(), 3);
+
+    let other_chunks = Options {
+        chunk_size: 5,
+        ..options.clone()
+    };
+    assert!(brute_force(&almanac, &other_chunks).is_err());
+
+    // same number of chunks, but a different map
+    let mut other_almanac = almanac.clone();
+    other_almanac.maps[1].ranges[0].dest_start = 1;
+    let err = brute_force(&other_almanac, &options).unwrap_err();
+    assert!(err.to_string().contains("another almanac"), "{}", err);
+
+    // the fingerprint is the same on every platform and Rust version
+    assert_eq!(fingerprint(&almanac), 0x59b0d0de5968922b);
+
+    // seeds at the very end of u64
+    let last_seeds = Almanac::parse(&format!(
+        "seeds: {} 3\n\nseed-to-location map:\n0 0 1",
+        u64::MAX - 3
+    ))
+    .unwrap();
+    let options = Options {
+        chunk_size: u64::MAX,
+        checkpoint: None,
+        ..options
+    };
+    assert_eq!(brute_force(&last_seeds, &options).unwrap(), u64::MAX - 3);
+    let too_far = Almanac::parse(&format!(
+        "seeds: {} 4\n\nseed-to-location map:\n0 0 1",
+        u64::MAX - 3
+    ))
+    .unwrap();
+    assert!(brute_force(&too_far, &options).is_err());
+
+    fs::remove_file(path).unwrap();
+}
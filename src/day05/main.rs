@@ -1,181 +1,222 @@
 use std::{
-    cmp::Ordering,
     collections::{HashMap, HashSet},
     error::Error,
     fs,
     ops::Range,
 };
 
+use almanac::{Almanac, MappingRange};
+use brute_force::brute_force;
 use rand::{
     distributions::{Distribution, Uniform},
     Rng,
 };
-use regex::Regex;
+
+mod almanac;
+mod brute_force;
+mod piecewise;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let input: String = fs::read_to_string("src/day05/input")?.parse()?;
-    part1(&input)?;
-    part2_pso(&input)?;
+    let almanac = Almanac::parse(&input)?;
+
+    // `--strategy pso|naive|reverse` solves part 2 with one of the older approaches,
+    // none of them is guaranteed to be both correct and fast, pso misses the minimum
+    // of some random almanacs (see `pso_error_rate`)
+    // `--strategy brute` tries every seed on all cores, for a ground truth to compare to
+    let mut strategy = "exact".to_owned();
+    let mut brute_force_options = brute_force::Options {
+        progress: true,
+        ..Default::default()
+    };
 
-    Ok(())
-}
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strategy" => strategy = args.next().ok_or("Missing strategy")?,
+            "--threads" => {
+                brute_force_options.threads = args.next().ok_or("Missing thread count")?.parse()?
+            }
+            "--chunk-size" => {
+                brute_force_options.chunk_size = args.next().ok_or("Missing chunk size")?.parse()?
+            }
+            // `--checkpoint brute.txt` continues an interrupted brute force run
+            "--checkpoint" => {
+                brute_force_options.checkpoint = Some(args.next().ok_or("Missing path")?.into())
+            }
+            // `--trace 79,14` shows how the seeds 79 and 14 get to their locations
+            "--trace" => {
+                for seed in args.next().ok_or("Missing seeds")?.split(',') {
+                    println!("{}", trace_table(&almanac, seed.trim().parse()?)?);
+                }
+                return Ok(());
+            }
+            // `--check` lists overlapping and gapped ranges of every map
+            "--check" => {
+                for map in &almanac.maps {
+                    for issue in map.issues() {
+                        println!("{}-to-{}: {}", map.source, map.destination, issue);
+                    }
+                }
+                return Ok(());
+            }
+            // `--convert 79 seed humidity` only converts a single value
+            "--convert" => {
+                let value = args.next().ok_or("Missing value")?.parse()?;
+                let from = args.next().ok_or("Missing source category")?;
+                let to = args.next().ok_or("Missing destination category")?;
+                println!("{}", almanac.convert(value, &from, &to)?);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown argument: {}", arg).into()),
+        }
+    }
 
-#[derive(PartialEq, Eq)]
-struct MappingRange {
-    source_range: Range<u64>,
-    dest_start: u64,
-}
+    println!("The answer for part 1 is: {}", part1(&almanac)?);
 
-impl PartialOrd for MappingRange {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.source_range
-            .start
-            .partial_cmp(&other.source_range.start)
-    }
-}
+    let nearest_location = match strategy.as_str() {
+        "exact" => part2(&almanac)?,
+        "pso" => part2_pso(&almanac, &mut rand::thread_rng())?,
+        "naive" => part2_naive(&almanac)?,
+        "reverse" => part2_reverse_search(&almanac)?,
+        "brute" => brute_force(&almanac, &brute_force_options)?,
+        _ => return Err(format!("Unknown strategy: {}", strategy).into()),
+    };
+    println!("The answer for part 2 is: {}", nearest_location);
 
-impl Ord for MappingRange {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.source_range.start.cmp(&other.source_range.start)
-    }
+    Ok(())
 }
 
-fn part1(input: &str) -> Result<(), Box<dyn Error>> {
-    // find seed numbers in input and parse them as u32
-    let seeds: Vec<u64> = Regex::new(r"seeds:(.*)\n")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .collect();
-
-    // split off remaining input to process the maps
-    let rem_input_with_maps = input.split_once("map:").unwrap().1;
+/// One row per map with the value before and after it and the range that was used.
+fn trace_table(almanac: &Almanac, seed: u64) -> Result<String, Box<dyn Error>> {
+    let mut rows = vec![[
+        "map".to_owned(),
+        "value".to_owned(),
+        "range".to_owned(),
+        "offset".to_owned(),
+        "result".to_owned(),
+    ]];
+
+    for step in almanac.trace(seed)? {
+        let range = match step.range {
+            Some(range) => format!(
+                "{}..{} -> {}",
+                range.source_range.start, range.source_range.end, range.dest_start
+            ),
+            None => "identity gap".to_owned(),
+        };
+
+        rows.push([
+            format!("{}-to-{}", step.map.source, step.map.destination),
+            step.value.to_string(),
+            range,
+            format!("{:+}", step.offset),
+            step.result.to_string(),
+        ]);
+    }
 
-    // split remaining input into maps
-    let maps_str: Vec<&str> = Regex::new(r"\n.*map:.*\n") //
-        .unwrap()
-        .split(rem_input_with_maps)
-        .map(|x| x.trim())
+    let widths: Vec<usize> = (0..5)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
         .collect();
 
-    // process the maps from str into data structure
-    let mut mega_map: Vec<Vec<MappingRange>> = vec![];
-
-    for map_str in maps_str {
-        let mut map: Vec<MappingRange> = vec![];
-
-        for line in map_str.lines() {
-            // parse numbers in line
-            let x: Vec<u64> = line
-                .split_whitespace()
-                .map(|x| x.parse().unwrap())
-                .collect();
-
-            // create MappingRange and add to map vector
-            let source_start = x[1];
-            let range_len = x[2];
-            let source_end = source_start + range_len;
-            map.push(MappingRange {
-                source_range: source_start..source_end,
-                dest_start: x[0],
-            });
-        }
-        map.sort();
-        mega_map.push(map);
+    let mut table = format!("seed {}\n", seed);
+    for row in rows {
+        table.push_str(&format!(
+            "  {:<w0$}  {:>w1$}  {:<w2$}  {:>w3$}  {:>w4$}\n",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+        ));
     }
 
-    // find the locations, vec of (seed, location)
-    let mut locations: Vec<(u64, u64)> = vec![];
+    Ok(table)
+}
 
-    for seed in seeds {
-        let mut current_number = seed;
-
-        'map_loop: for map in &mega_map {
-            for mapping_range in map {
-                // current number is in a gap between ranges
-                // => number stays unchanged by this map
-                if mapping_range.source_range.start > current_number {
-                    continue 'map_loop;
-                }
+fn part1(almanac: &Almanac) -> Result<u64, Box<dyn Error>> {
+    let seed_to_location = almanac.compose("seed", "location")?;
 
-                // found in a mapping range, convert number and go to next map
-                if mapping_range.source_range.contains(&current_number) {
-                    let offset = current_number - mapping_range.source_range.start;
-                    current_number = mapping_range.dest_start + offset;
-                    continue 'map_loop;
-                }
-            }
-            // not found in a mapping range or gap, reached end
-            // => number stays unchanged by this map
-        }
+    // find the location of every seed
+    let nearest_location = almanac
+        .seeds
+        .iter()
+        .map(|&seed| seed_to_location.apply(seed))
+        .min()
+        .ok_or("No seeds")?;
 
-        // end of processing, found location
-        locations.push((seed, current_number));
-    }
+    Ok(nearest_location)
+}
 
-    let (_seed_to_use, nearest_location) = locations.iter().min_by(|x, y| x.1.cmp(&y.1)).unwrap();
+fn part2(almanac: &Almanac) -> Result<u64, Box<dyn Error>> {
+    let seed_to_location = almanac.compose("seed", "location")?;
 
-    println!("The answer for part 1 is: {}", nearest_location);
+    // push whole seed ranges through the maps instead of single seeds
+    let seed_ranges = almanac.seed_ranges()?;
+    let ranges = map_ranges(seed_to_location.pieces(), &seed_ranges);
 
-    Ok(())
-}
+    // every location in the resulting ranges is reached, so the smallest start is the answer
+    let nearest_location = ranges
+        .iter()
+        .map(|range| range.start)
+        .min()
+        .ok_or("No seeds")?;
 
-fn part2_pso(input: &str) -> Result<(), Box<dyn Error>> {
-    // find seed numbers in input and parse them as u32
-    let raw_seeds: Vec<u64> = Regex::new(r"seeds:(.*)\n")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .collect();
-    let seed_ranges: Vec<&[u64]> = raw_seeds.chunks(2).collect();
+    Ok(nearest_location)
+}
 
-    // split off remaining input to process the maps
-    let rem_input_with_maps = input.split_once("map:").unwrap().1;
+/// Applies `map` (sorted by source start, without overlaps) to all numbers in `ranges`, splitting them
+/// where they cross the boundaries of a mapping range. No empty ranges are returned.
+fn map_ranges(map: &[MappingRange], ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut mapped = vec![];
 
-    // split remaining input into maps
-    let maps_str: Vec<&str> = Regex::new(r"\n.*map:.*\n") //
-        .unwrap()
-        .split(rem_input_with_maps)
-        .map(|x| x.trim())
-        .collect();
+    for range in ranges {
+        let mut start = range.start;
 
-    // process the maps from str into data structure
-    let mut mega_map: Vec<Vec<MappingRange>> = vec![];
+        for mapping_range in map {
+            let source = &mapping_range.source_range;
 
-    for map_str in maps_str {
-        let mut map: Vec<MappingRange> = vec![];
+            if start >= range.end {
+                break;
+            }
+            if source.end <= start {
+                continue;
+            }
 
-        for line in map_str.lines() {
-            // parse numbers in line
-            let x: Vec<u64> = line
-                .split_whitespace()
-                .map(|x| x.parse().unwrap())
-                .collect();
+            // part in the gap before this mapping range stays unchanged
+            if source.start > start {
+                let end = u64::min(range.end, source.start);
+                mapped.push(start..end);
+                start = end;
+            }
 
-            // create MappingRange and add to map vector
-            let source_start = x[1];
-            let range_len = x[2];
-            let source_end = source_start + range_len;
+            // part inside this mapping range is shifted
+            let end = u64::min(range.end, source.end);
+            if end > start {
+                let dest_start = mapping_range.dest_start + (start - source.start);
+                mapped.push(dest_start..dest_start + (end - start));
+                start = end;
+            }
+        }
 
-            map.push(MappingRange {
-                source_range: source_start..source_end,
-                dest_start: x[0],
-            });
+        // part after the last mapping range stays unchanged
+        if start < range.end {
+            mapped.push(start..range.end);
         }
-        map.sort();
-        mega_map.push(map);
     }
 
+    mapped
+}
+
+fn part2_pso(almanac: &Almanac, rng: &mut impl Rng) -> Result<u64, Box<dyn Error>> {
+    let route = almanac.route("seed", "location")?;
+    let seed_ranges = almanac.seed_ranges()?;
+
     // particle swarm optimization for each seed range
     struct Particle {
         x: u64,
@@ -183,51 +224,30 @@ fn part2_pso(input: &str) -> Result<(), Box<dyn Error>> {
         v: i64,
     }
 
-    let cost_function = |seed| {
-        let mut i = seed;
-
-        'map_loop: for map in &mega_map {
-            for mapping_range in map {
-                // current number is in a gap between ranges
-                // => number stays unchanged by this map
-                if mapping_range.source_range.start > i {
-                    continue 'map_loop;
-                }
-
-                // found in a mapping range, convert number and go to next map
-                if mapping_range.source_range.contains(&i) {
-                    let offset = i - mapping_range.source_range.start;
-                    i = mapping_range.dest_start + offset;
-                    continue 'map_loop;
-                }
-            }
-            // not found in a mapping range or gap, reached end
-            // => number stays unchanged by this map
-        }
-
-        i
-    };
+    let cost_function = |seed| route.iter().fold(seed, |value, map| map.apply(value));
 
     let mut best_location_per_range: Vec<(u64, u64)> = vec![];
-    let mut rng = rand::thread_rng();
 
-    for seed_range in seed_ranges {
-        let seed_range_start = seed_range[0];
-        let seed_range_length = seed_range[1];
-        let seed_range_end = seed_range_start + seed_range_length;
+    // there is nothing to sample from an empty range
+    for seed_range in seed_ranges.iter().filter(|range| !range.is_empty()) {
+        let seed_range_start = seed_range.start;
+        let seed_range_end = seed_range.end;
+        let seed_range_length = seed_range_end - seed_range_start;
         let position_dist = Uniform::from(seed_range_start..seed_range_end);
-        let velocity_dist = Uniform::from(-(seed_range_length as i64)..(seed_range_length as i64));
+        // ranges can be longer than i64::MAX, the speed is capped there
+        let max_velocity = i64::try_from(seed_range_length).unwrap_or(i64::MAX);
+        let velocity_dist = Uniform::from(-max_velocity..max_velocity);
 
         let mut particles: Vec<Particle> = vec![];
-        let mut best_swarm_position = (0u64, u32::MAX as u64);
+        let mut best_swarm_position = (0u64, u64::MAX);
         let mut steps_best_swarm_position_unchanged = 0;
         let nr_particles = 10000;
 
         // init particles
         for _ in 0..nr_particles {
-            let x = position_dist.sample(&mut rng);
+            let x = position_dist.sample(rng);
             let x_cost = cost_function(x);
-            let v = velocity_dist.sample(&mut rng);
+            let v = velocity_dist.sample(rng);
 
             let particle = Particle {
                 x,
@@ -249,32 +269,24 @@ fn part2_pso(input: &str) -> Result<(), Box<dyn Error>> {
                 let r_p: f32 = rng.gen();
                 let r_g: f32 = rng.gen();
 
-                // movement vector components
-                let diff_particle = particle.best_x.0 as i64 - particle.x as i64;
-                let diff_swarm = best_swarm_position.0 as i64 - particle.x as i64;
+                // movement vector components, seeds can be above i64::MAX
+                let diff_particle = particle.best_x.0 as i128 - particle.x as i128;
+                let diff_swarm = best_swarm_position.0 as i128 - particle.x as i128;
 
                 // calc new velocity
                 let new_velocity = (
                     // inertia
-                    0.5 * particle.v as f32 
+                    0.5 * particle.v as f32
                     // towards particle best
-                    + 1.5 * r_p * diff_particle as f32 
+                    + 1.5 * r_p * diff_particle as f32
                     // towards swarm best
                     + 2.5 * r_g * diff_swarm as f32
                 ) as i64;
 
-                // add velocity to position
-                let mut new_position = (particle.x as i64 + new_velocity) as u64;
-
-                // clamp to seed range
-                new_position = u64::max(
-                    seed_range_start,
-                    new_position
-                );
-                new_position = u64::min(
-                    seed_range_end - 1,
-                    new_position
-                );
+                // add velocity to position and clamp to seed range
+                let new_position = (particle.x as i128 + new_velocity as i128)
+                    .clamp(seed_range_start as i128, seed_range_end as i128 - 1)
+                    as u64;
 
                 // calc cost
                 let cost_new_position = cost_function(new_position);
@@ -304,232 +316,311 @@ fn part2_pso(input: &str) -> Result<(), Box<dyn Error>> {
     let (best_candidate_pso_seed, _best_candidate_pso_location) = best_location_per_range
         .iter()
         .min_by(|x, y| x.1.cmp(&y.1))
-        .unwrap();
+        .ok_or("No seeds")?;
 
     // fine search near pso candidate
-    let start = best_candidate_pso_seed - 1_000_000;
-    let end = best_candidate_pso_seed + 1_000_000;
+    let start = best_candidate_pso_seed.saturating_sub(1_000_000);
+    let end = best_candidate_pso_seed.saturating_add(1_000_000);
     let mut best_location = u64::MAX;
+    let is_seed = |seed: &u64| seed_ranges.iter().any(|range| range.contains(seed));
 
-    for seed in start..end {
+    for seed in (start..end).filter(is_seed) {
         let location = cost_function(seed);
         best_location = u64::min(location, best_location);
     }
 
-    // done
-    println!("The answer for part 2 is: {}", best_location);
-
-    Ok(())
+    Ok(best_location)
 }
 
-fn part2_naive(input: &str) -> Result<(), Box<dyn Error>> {
-    // find seed numbers in input and parse them as u32
-    let raw_seeds: Vec<u64> = Regex::new(r"seeds:(.*)\n")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .collect();
-    let seed_pairs: Vec<&[u64]> = raw_seeds.chunks(2).collect();
+fn part2_naive(almanac: &Almanac) -> Result<u64, Box<dyn Error>> {
+    let route = almanac.route("seed", "location")?;
     let mut seeds: HashSet<u64> = HashSet::new();
 
-    for seed_pair in seed_pairs {
-        let seed_range_start = seed_pair[0];
-        let seed_range_length = seed_pair[1];
-        let seed_range_end = seed_range_start + seed_range_length;
-
-        for seed in seed_range_start..seed_range_end {
-            seeds.insert(seed);
-        }
-    }
-
-    // split off remaining input to process the maps
-    let rem_input_with_maps = input.split_once("map:").unwrap().1;
-
-    // split remaining input into maps
-    let maps_str: Vec<&str> = Regex::new(r"\n.*map:.*\n") //
-        .unwrap()
-        .split(rem_input_with_maps)
-        .map(|x| x.trim())
-        .collect();
-
-    // process the maps from str into data structure
-    let mut mega_map: Vec<Vec<MappingRange>> = vec![];
-
-    for map_str in maps_str {
-        let mut map: Vec<MappingRange> = vec![];
-
-        for line in map_str.lines() {
-            // parse numbers in line
-            let x: Vec<u64> = line
-                .split_whitespace()
-                .map(|x| x.parse().unwrap())
-                .collect();
-
-            // create MappingRange and add to map vector
-            let source_start = x[1];
-            let range_len = x[2];
-            let source_end = source_start + range_len;
-            map.push(MappingRange {
-                source_range: source_start..source_end,
-                dest_start: x[0],
-            });
-        }
-        map.sort();
-        mega_map.push(map);
+    for seed_range in almanac.seed_ranges()? {
+        seeds.extend(seed_range);
     }
 
     // find the locations, vec of (seed, location)
     let mut seeds_locations: HashMap<u64, u64> = HashMap::new();
 
     for seed in seeds {
-        let mut current_number = seed;
-
-        'map_loop: for map in &mega_map {
-            for mapping_range in map {
-                // current number is in a gap between ranges
-                // => number stays unchanged by this map
-                if mapping_range.source_range.start > current_number {
-                    continue 'map_loop;
-                }
-
-                // found in a mapping range, convert number and go to next map
-                if mapping_range.source_range.contains(&current_number) {
-                    let offset = current_number - mapping_range.source_range.start;
-                    current_number = mapping_range.dest_start + offset;
-                    continue 'map_loop;
-                }
-            }
-            // not found in a mapping range or gap, reached end
-            // => number stays unchanged by this map
-        }
+        let location = route.iter().fold(seed, |value, map| map.apply(value));
 
         // end of processing, found location
-        seeds_locations.insert(seed, current_number);
+        seeds_locations.insert(seed, location);
     }
 
-    let (_seed_to_use, nearest_location) =
-        seeds_locations.iter().min_by(|x, y| x.1.cmp(&y.1)).unwrap();
-
-    println!("The answer for part 2 is: {}", nearest_location);
+    let (_seed_to_use, nearest_location) = seeds_locations
+        .iter()
+        .min_by(|x, y| x.1.cmp(y.1))
+        .ok_or("No seeds")?;
 
-    Ok(())
+    Ok(*nearest_location)
 }
 
-#[derive(PartialEq, Eq)]
-struct ReverseMappingRange {
-    dest_range: Range<u64>,
-    source_start: u64,
-}
+fn part2_reverse_search(almanac: &Almanac) -> Result<u64, Box<dyn Error>> {
+    let mut seed_ranges = almanac.seed_ranges()?;
+    seed_ranges.retain(|range| !range.is_empty());
+    seed_ranges.sort_by_key(|range| (range.start, range.end));
+
+    // no location past the ones the seeds reach has to be tried
+    let seed_to_location = almanac.compose("seed", "location")?;
+    let location_end = map_ranges(seed_to_location.pieces(), &seed_ranges)
+        .iter()
+        .map(|range| range.end)
+        .max()
+        .ok_or("No seeds")?;
+
+    // look up seeds by location
+    let location_to_seed = seed_to_location.inverse()?;
+
+    // iterate over possible locations starting from lowest
+    // determine if necessary seeds are available
+    'location_loop: for location in 0..location_end {
+        let i = location_to_seed.apply(location);
+
+        // end of processing, check if necessary seed is available
+        for seed_range in &seed_ranges {
+            // necessary seed in gap, not available, test next location
+            if i < seed_range.start {
+                continue 'location_loop;
+            }
 
-impl PartialOrd for ReverseMappingRange {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.dest_range.start.partial_cmp(&other.dest_range.start)
+            // found the location
+            if seed_range.contains(&i) {
+                return Ok(location);
+            }
+        }
     }
+
+    Err("No seeds".into())
 }
 
-impl Ord for ReverseMappingRange {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.dest_range.start.cmp(&other.dest_range.start)
-    }
+#[test]
+fn examples() {
+    let example_input = "
+        seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-fertilizer map:
+        0 15 37
+        37 52 2
+        39 0 15
+
+        fertilizer-to-water map:
+        49 53 8
+        0 11 42
+        42 0 7
+        57 7 4
+
+        water-to-light map:
+        88 18 7
+        18 25 70
+
+        light-to-temperature map:
+        45 77 23
+        81 45 19
+        68 64 13
+
+        temperature-to-humidity map:
+        0 69 1
+        1 0 69
+
+        humidity-to-location map:
+        60 56 37
+        56 93 4
+    ";
+
+    let almanac = Almanac::parse(example_input).unwrap();
+    assert_eq!(part1(&almanac).unwrap(), 35);
+    assert_eq!(part2(&almanac).unwrap(), 46);
+    assert_eq!(part2_naive(&almanac).unwrap(), 46);
+    assert_eq!(part2_reverse_search(&almanac).unwrap(), 46);
 }
-fn part2_reverse_search(input: &str) -> Result<(), Box<dyn Error>> {
-    // find seed numbers in input and parse them as u32
-    let raw_seeds: Vec<u64> = Regex::new(r"seeds:(.*)\n")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .collect();
-    let mut seed_pairs: Vec<&[u64]> = raw_seeds.chunks(2).collect();
-    seed_pairs.sort();
 
-    // split off remaining input to process the maps
-    let rem_input_with_maps = input.split_once("map:").unwrap().1;
+#[test]
+fn range_splitting() {
+    let map = [
+        MappingRange {
+            source_range: 10..20,
+            dest_start: 100,
+        },
+        MappingRange {
+            source_range: 25..30,
+            dest_start: 0,
+        },
+    ];
+
+    assert_eq!(
+        map_ranges(&map, &[5..27, 29..40, 40..40]),
+        vec![5..10, 100..110, 20..25, 0..2, 4..5, 30..40]
+    );
+}
 
-    // split remaining input into maps
-    let maps_str: Vec<&str> = Regex::new(r"\n.*map:.*\n") //
-        .unwrap()
-        .split(rem_input_with_maps)
-        .map(|x| x.trim())
+/// Random almanac with up to 4 maps and up to 3 seed ranges, all numbers below
+/// `100 * scale`. Without `invertible` the ranges are placed independently, so they
+/// overlap and leave gaps. Otherwise the maps only shuffle blocks of numbers around
+/// within runs of blocks, the blocks between those runs are left as gaps.
+#[cfg(test)]
+fn random_almanac(rng: &mut impl Rng, invertible: bool, scale: u64) -> Almanac {
+    use almanac::CategoryMap;
+    use rand::seq::SliceRandom;
+
+    let seeds = (0..rng.gen_range(1..=3))
+        .flat_map(|_| [rng.gen_range(0..90) * scale, rng.gen_range(1..=10) * scale])
         .collect();
 
-    // process the maps from str into data structure
-    let mut mega_map: Vec<Vec<ReverseMappingRange>> = vec![];
+    let nr_maps = rng.gen_range(1..=4);
+    let mut categories: Vec<String> = (0..nr_maps).map(|i| format!("c{}", i)).collect();
+    categories[0] = "seed".to_owned();
+    categories.push("location".to_owned());
 
-    for map_str in maps_str {
-        let mut map: Vec<ReverseMappingRange> = vec![];
+    let mut maps = vec![];
+    for pair in categories.windows(2) {
+        let mut ranges = vec![];
 
-        for line in map_str.lines() {
-            // parse numbers in line
-            let x: Vec<u64> = line
-                .split_whitespace()
-                .map(|x| x.parse().unwrap())
+        if invertible {
+            let mut cuts: Vec<u64> = (0..rng.gen_range(1..8))
+                .map(|_| rng.gen_range(1..100))
+                .chain([0, 100])
                 .collect();
+            cuts.sort();
+            cuts.dedup();
+
+            // runs of consecutive blocks that get shuffled, the other blocks are gaps
+            let mut runs: Vec<Vec<Range<u64>>> = vec![vec![]];
+            for block in cuts.windows(2).map(|cut| cut[0]..cut[1]) {
+                if rng.gen_bool(0.3) {
+                    runs.push(vec![]);
+                } else {
+                    runs.last_mut().unwrap().push(block);
+                }
+            }
 
-            // create MappingRange and add to map vector
-            let dest_start = x[0];
-            let range_len = x[2];
-            let dest_end = dest_start + range_len;
-            map.push(ReverseMappingRange {
-                dest_range: dest_start..dest_end,
-                source_start: x[1],
-            });
+            for run in runs.into_iter().filter(|run| !run.is_empty()) {
+                let mut dest = run[0].start;
+                let mut shuffled = run.clone();
+                shuffled.shuffle(rng);
+
+                for block in shuffled {
+                    ranges.push(MappingRange {
+                        source_range: block.start * scale..block.end * scale,
+                        dest_start: dest * scale,
+                    });
+                    dest += block.end - block.start;
+                }
+            }
+        } else {
+            for _ in 0..rng.gen_range(1..=4) {
+                let start = rng.gen_range(0..100);
+                ranges.push(MappingRange {
+                    source_range: start * scale..(start + rng.gen_range(1..=30)) * scale,
+                    dest_start: rng.gen_range(0..100) * scale,
+                });
+            }
         }
-        map.sort();
-        mega_map.push(map);
+
+        ranges.sort();
+        maps.push(CategoryMap {
+            source: pair[0].clone(),
+            destination: pair[1].clone(),
+            ranges,
+        });
     }
 
-    // iterate over possible locations starting from lowest
-    // determine if necessary seeds are available
-    'location_loop: for location in 0..u64::MAX {
-        let mut i = location;
-
-        'map_loop: for map in mega_map.iter().rev() {
-            for reverse_mapping_range in map {
-                // current number is in a gap between ranges
-                // => number stays unchanged by this map
-                if reverse_mapping_range.dest_range.start > i {
-                    continue 'map_loop;
-                }
+    Almanac { seeds, maps }
+}
 
-                // found in a mapping range, convert number and go to next map
-                if reverse_mapping_range.dest_range.contains(&i) {
-                    let offset = i - reverse_mapping_range.dest_range.start;
-                    i = reverse_mapping_range.source_start + offset;
-                    continue 'map_loop;
-                }
-            }
-            // not found in a mapping range or gap, reached end
-            // => number stays unchanged by this map
+#[test]
+fn strategies_agree() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(5);
+
+    for _ in 0..500 {
+        let invertible = rng.gen_bool(0.5);
+        let almanac = random_almanac(&mut rng, invertible, 1);
+        let exact = part2(&almanac).unwrap();
+
+        assert_eq!(part2_naive(&almanac).unwrap(), exact, "{:?}", almanac);
+
+        let options = brute_force::Options {
+            threads: 2,
+            chunk_size: 7,
+            ..Default::default()
+        };
+        assert_eq!(
+            brute_force(&almanac, &options).unwrap(),
+            exact,
+            "{:?}",
+            almanac
+        );
+
+        // the reverse search needs a single seed for every location
+        match part2_reverse_search(&almanac) {
+            Ok(reverse) => assert_eq!(reverse, exact, "{:?}", almanac),
+            Err(_) => assert!(!invertible, "{:?}", almanac),
         }
+    }
+}
 
-        // end of processing, check if necessary seed is available
-        for seed_pair in &seed_pairs {
-            let start = seed_pair[0];
-            let length = seed_pair[1];
-            let end = start + length;
+#[test]
+fn edge_seed_ranges() {
+    use rand::{rngs::StdRng, SeedableRng};
 
-            // necessary seed in gap, not available, test next location
-            if i < seed_pair[0] {
-                continue 'location_loop;
-            }
+    let mut rng = StdRng::seed_from_u64(3);
+    let almanac = |seeds: &str| {
+        Almanac::parse(&format!("seeds: {}\n\nseed-to-location map:\n0 0 1", seeds)).unwrap()
+    };
 
-            // found the location
-            if (start..end).contains(&i) {
-                println!("The answer for part 2 is: {}", location);
-                break 'location_loop;
-            }
+    // only empty ranges, every strategy has to give up instead of searching
+    let empty = almanac("5 0");
+    assert!(part2(&empty).is_err());
+    assert!(part2_naive(&empty).is_err());
+    assert!(part2_reverse_search(&empty).is_err());
+    assert!(part2_pso(&empty, &mut rng).is_err());
+    assert!(brute_force(&empty, &Default::default()).is_err());
+
+    // longer than i64::MAX and above it
+    let long = almanac("0 10000000000000000000");
+    assert_eq!(part2(&long).unwrap(), 0);
+    assert!(part2_pso(&long, &mut rng).is_ok());
+
+    let high = almanac(&format!("{} 1000", u64::MAX - 1000));
+    assert_eq!(part2(&high).unwrap(), u64::MAX - 1000);
+    assert!(part2_pso(&high, &mut rng).unwrap() >= u64::MAX - 1000);
+}
+
+/// PSO gives no guarantees, this measures how often it misses on almanacs where
+/// the seed ranges are too large for its fine search to cover them completely, and
+/// fails if that gets worse than one in ten.
+/// Slow, run with `cargo test --release --bin day05 -- --ignored --nocapture pso_error_rate`.
+#[test]
+#[ignore]
+fn pso_error_rate() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let runs = 300;
+    let mut wrong = 0;
+
+    for _ in 0..runs {
+        let invertible = rng.gen_bool(0.5);
+        let almanac = random_almanac(&mut rng, invertible, 1_000_000_000);
+
+        if part2_pso(&almanac, &mut rng).unwrap() != part2(&almanac).unwrap() {
+            wrong += 1;
         }
     }
 
-    Ok(())
+    println!("PSO was wrong in {} of {} runs", wrong, runs);
+    assert!(
+        wrong * 10 <= runs,
+        "PSO was wrong in {} of {} runs",
+        wrong,
+        runs
+    );
 }
@@ -0,0 +1,185 @@
+use crate::almanac::{CategoryMap, MappingRange};
+use std::{cmp::Ordering, ops::Range};
+
+/// A map as a function on all of `0..u64::MAX`: disjoint pieces sorted by source
+/// start and without holes, numbers outside of the mapping ranges are covered by
+/// identity pieces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piecewise {
+    pieces: Vec<MappingRange>,
+}
+
+impl Piecewise {
+    pub fn identity() -> Self {
+        Piecewise {
+            pieces: vec![MappingRange {
+                source_range: 0..u64::MAX,
+                dest_start: 0,
+            }],
+        }
+    }
+
+    /// Overlapping ranges are cut so that the one starting first wins, as in
+    /// [`CategoryMap::apply`].
+    pub fn from_map(map: &CategoryMap) -> Self {
+        let mut pieces = vec![];
+        let mut covered = 0;
+
+        for range in &map.ranges {
+            let start = u64::max(range.source_range.start, covered);
+            if range.source_range.end <= start {
+                continue;
+            }
+
+            push_piece(&mut pieces, covered..start, covered);
+            push_piece(
+                &mut pieces,
+                start..range.source_range.end,
+                range.dest_start + (start - range.source_range.start),
+            );
+            covered = range.source_range.end;
+        }
+        push_piece(&mut pieces, covered..u64::MAX, covered);
+
+        Piecewise { pieces }
+    }
+
+    pub fn pieces(&self) -> &[MappingRange] {
+        &self.pieces
+    }
+
+    /// Binary search for the piece containing `value`.
+    pub fn apply(&self, value: u64) -> u64 {
+        let i = self
+            .pieces
+            .partition_point(|piece| piece.source_range.end <= value);
+
+        match self.pieces.get(i) {
+            Some(piece) => piece.dest_start + (value - piece.source_range.start),
+            None => value,
+        }
+    }
+
+    /// The function applying `self` first and `next` on its result.
+    pub fn then(&self, next: &Piecewise) -> Piecewise {
+        let mut pieces = vec![];
+
+        for piece in &self.pieces {
+            let source_start = piece.source_range.start;
+            let len = piece.source_range.end - source_start;
+            let dest_end = piece.dest_start.saturating_add(len);
+            let mut dest = piece.dest_start;
+
+            // split the destination of the piece wherever a piece of `next` starts
+            let first = next
+                .pieces
+                .partition_point(|next_piece| next_piece.source_range.end <= dest);
+
+            for next_piece in &next.pieces[first..] {
+                if dest >= dest_end {
+                    break;
+                }
+
+                let end = u64::min(dest_end, next_piece.source_range.end);
+                let source = source_start + (dest - piece.dest_start);
+                push_piece(
+                    &mut pieces,
+                    source..source + (end - dest),
+                    next_piece.dest_start + (dest - next_piece.source_range.start),
+                );
+                dest = end;
+            }
+        }
+
+        Piecewise { pieces }
+    }
+
+    /// The function mapping every destination back to its source. Fails if two numbers
+    /// are mapped to the same destination or some destination is never reached.
+    pub fn inverse(&self) -> Result<Piecewise, String> {
+        let mut by_dest: Vec<&MappingRange> = self.pieces.iter().collect();
+        by_dest.sort_by_key(|piece| piece.dest_start);
+
+        let mut pieces = vec![];
+        let mut covered = 0;
+
+        for piece in by_dest {
+            let len = piece.source_range.end - piece.source_range.start;
+
+            match piece.dest_start.cmp(&covered) {
+                Ordering::Less => return Err(format!("{} is reached twice", piece.dest_start)),
+                Ordering::Greater => return Err(format!("{} is never reached", covered)),
+                Ordering::Equal => {}
+            }
+
+            push_piece(
+                &mut pieces,
+                piece.dest_start..piece.dest_start + len,
+                piece.source_range.start,
+            );
+            covered = piece.dest_start + len;
+        }
+
+        Ok(Piecewise { pieces })
+    }
+}
+
+/// Appends a piece, merging it into the previous one if that continues with the same offset.
+fn push_piece(pieces: &mut Vec<MappingRange>, source_range: Range<u64>, dest_start: u64) {
+    if source_range.is_empty() {
+        return;
+    }
+
+    if let Some(last) = pieces.last_mut() {
+        let last_len = last.source_range.end - last.source_range.start;
+        if last.source_range.end == source_range.start
+            && last.dest_start.checked_add(last_len) == Some(dest_start)
+        {
+            last.source_range.end = source_range.end;
+            return;
+        }
+    }
+
+    pieces.push(MappingRange {
+        source_range,
+        dest_start,
+    });
+}
+
+#[test]
+fn compose_and_invert() {
+    use crate::almanac::Almanac;
+
+    let almanac = Almanac::parse(
+        "seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-location map:
+        0 15 37
+        37 52 2
+        39 0 15",
+    )
+    .unwrap();
+
+    let seed_to_soil = Piecewise::from_map(&almanac.maps[0]);
+    let soil_to_fertilizer = Piecewise::from_map(&almanac.maps[1]);
+    assert_eq!(seed_to_soil.pieces().len(), 4);
+    assert_eq!(seed_to_soil.apply(99), 51);
+    assert_eq!(seed_to_soil.apply(10), 10);
+
+    let composed = seed_to_soil.then(&soil_to_fertilizer);
+    for seed in 0..200 {
+        let fertilizer = soil_to_fertilizer.apply(seed_to_soil.apply(seed));
+        assert_eq!(composed.apply(seed), fertilizer);
+        assert_eq!(composed.inverse().unwrap().apply(fertilizer), seed);
+    }
+    assert_eq!(composed.then(&Piecewise::identity()), composed);
+
+    // both 0..2 and 5..7 end up at 0..2
+    let squashing = Almanac::parse("seeds: 1\n\nseed-to-location map:\n0 5 2").unwrap();
+    let squashing = Piecewise::from_map(&squashing.maps[0]);
+    assert_eq!(squashing.inverse(), Err("0 is reached twice".to_owned()));
+}
@@ -1,112 +1,79 @@
-use regex::Regex;
+use race::{parse_races, report, Kerning, Race};
 use std::{error::Error, fs};
 
+mod race;
+
 fn main() -> Result<(), Box<dyn Error>> {
     let input: String = fs::read_to_string("src/day06/input")?.parse()?;
-    part1(&input)?;
-    part2(&input)?;
-
-    Ok(())
-}
+    let options = parse_args()?;
 
-fn part1(input: &str) -> Result<(), Box<dyn Error>> {
-    let times: Vec<u64> = Regex::new(r"Time:(.*)\n")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .collect();
-
-    let distances: Vec<u64> = Regex::new(r"Distance:(.*)\z")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .collect();
-
-    let mut results = vec![];
-
-    for (&available_time, min_distance) in times.iter().zip(distances) {
-        let mut count = 0u32;
-        let mut success_zone = false;
-
-        for t_button_down in 1..available_time {
-            let distance = t_button_down * (available_time - t_button_down);
-
-            if distance > min_distance {
-                count += 1;
-                success_zone = true;
-            } else {
-                // stop iterating, not working any more
-                if success_zone == true {
-                    break;
-                }
-            }
+    if let Some(kerning) = &options.kerning {
+        let races = parse_races(&input, kerning)?;
+        if options.report {
+            print!("{}", report(&races));
         }
-
-        results.push(count);
+        println!("The answer is: {}", margin(&races)?);
+        return Ok(());
     }
 
-    let product: u32 = results.iter().product();
+    let separate = parse_races(&input, &Kerning::Separate)?;
+    let kerned = parse_races(&input, &Kerning::Kerned)?;
+
+    if options.report {
+        print!("{}\n{}\n", report(&separate), report(&kerned));
+    }
 
-    println!("The answer for part 1 is: {}", product);
+    println!("The answer for part 1 is: {}", margin(&separate)?);
+    println!("The answer for part 2 is: {}", margin(&kerned)?);
 
     Ok(())
 }
 
-fn part2(input: &str) -> Result<(), Box<dyn Error>> {
-    let available_time: u64 = Regex::new(r"Time:(.*)\n")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .collect::<Vec<&str>>()
-        .join("")
-        .parse()
-        .unwrap();
-
-    let min_distance: u64 = Regex::new(r"Distance:(.*)\z")
-        .unwrap()
-        .captures(input)
-        .unwrap()
-        .get(1) // index 1 is the capture group in the regex
-        .unwrap()
-        .as_str()
-        .split_whitespace()
-        .collect::<Vec<&str>>()
-        .join("")
-        .parse()
-        .unwrap();
-
-    let mut count = 0u32;
-    let mut success_zone = false;
-
-    for t_button_down in 1..available_time {
-        let distance = t_button_down * (available_time - t_button_down);
-
-        if distance > min_distance {
-            count += 1;
-            success_zone = true;
-        } else {
-            // stop iterating, not working any more
-            if success_zone == true {
-                break;
-            }
+struct Options {
+    kerning: Option<Kerning>,
+    report: bool,
+}
+
+/// `--kerning separate|kerned|groups=2,1` reads the races in a single way and
+/// solves only that, `--report` shows the winning hold times of every race.
+fn parse_args() -> Result<Options, Box<dyn Error>> {
+    let mut kerning = None;
+    let mut report = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--kerning" => kerning = Some(args.next().ok_or("Missing kerning")?.parse()?),
+            "--report" => report = true,
+            _ => return Err(format!("Unknown argument: {}", arg).into()),
         }
     }
 
-    println!("The answer for part 2 is: {}", count);
+    Ok(Options { kerning, report })
+}
 
-    Ok(())
+/// Product of the number of ways to win each race.
+fn margin(races: &[Race]) -> Result<u128, String> {
+    races.iter().try_fold(1u128, |product, race| {
+        product
+            .checked_mul(race.ways_to_win())
+            .ok_or("Product of the ways to win overflows u128".to_owned())
+    })
+}
+
+#[test]
+fn examples() {
+    let example_input = "
+        Time:      7  15   30
+        Distance:  9  40  200
+    ";
+
+    let separate = parse_races(example_input, &Kerning::Separate).unwrap();
+    assert_eq!(margin(&separate), Ok(288));
+
+    let kerned = parse_races(example_input, &Kerning::Kerned).unwrap();
+    assert_eq!(margin(&kerned), Ok(71503));
+
+    let grouped = parse_races(example_input, &Kerning::Groups(vec![1, 2])).unwrap();
+    assert_eq!(margin(&grouped), Ok(4 * 1477));
 }
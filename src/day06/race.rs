@@ -0,0 +1,271 @@
+use advent_of_code_2023::{math::winning_interval, parsing::section};
+use std::{ops::RangeInclusive, str::FromStr};
+
+/// One race: the time available and the record distance to beat, in millimetres.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u128,
+    pub record: u128,
+}
+
+impl Race {
+    /// The hold times that beat the record, `None` if there are none.
+    pub fn winning_interval(&self) -> Option<RangeInclusive<u128>> {
+        winning_interval(self.time, self.record)
+    }
+
+    /// How many hold times beat the record.
+    pub fn ways_to_win(&self) -> u128 {
+        match self.winning_interval() {
+            Some(interval) => interval.end() - interval.start() + 1,
+            None => 0,
+        }
+    }
+
+    /// The hold time going furthest, for odd times `time - optimal_hold()` goes just as far.
+    pub fn optimal_hold(&self) -> u128 {
+        self.time / 2
+    }
+
+    /// Distance travelled when holding the button for `hold` ms, `None` if it doesn't fit.
+    pub fn distance(&self, hold: u128) -> Option<u128> {
+        hold.checked_mul(self.time.checked_sub(hold)?)
+    }
+}
+
+/// How the columns of the `Time:` and `Distance:` lines are read as races.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kerning {
+    /// Every column is a race of its own (part 1).
+    Separate,
+    /// The spaces are bad kerning, all columns are the digits of a single race (part 2).
+    Kerned,
+    /// Each group of consecutive columns is one race, e.g. `[2, 1]` joins the first two.
+    Groups(Vec<usize>),
+}
+
+impl FromStr for Kerning {
+    type Err = String;
+
+    /// `separate`, `kerned` or `groups=2,1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "separate" => Ok(Kerning::Separate),
+            "kerned" => Ok(Kerning::Kerned),
+            _ => {
+                let groups = s
+                    .strip_prefix("groups=")
+                    .ok_or(format!("Unknown kerning `{}`", s))?;
+
+                let groups: Vec<usize> = groups
+                    .split(',')
+                    .map(|size| match size.trim().parse() {
+                        Ok(0) | Err(_) => Err(format!("Invalid group size `{}`", size)),
+                        Ok(size) => Ok(size),
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Kerning::Groups(groups))
+            }
+        }
+    }
+}
+
+/// The races of the input, the digits of the columns joined as `kerning` says.
+pub fn parse_races(input: &str, kerning: &Kerning) -> Result<Vec<Race>, String> {
+    let times = columns(input, "Time")?;
+    let records = columns(input, "Distance")?;
+
+    if times.len() != records.len() {
+        return Err(format!(
+            "{} times but {} distances",
+            times.len(),
+            records.len()
+        ));
+    }
+
+    let groups = match kerning {
+        Kerning::Separate => vec![1; times.len()],
+        Kerning::Kerned if times.is_empty() => vec![],
+        Kerning::Kerned => vec![times.len()],
+        Kerning::Groups(groups) => {
+            if groups.iter().sum::<usize>() != times.len() {
+                return Err(format!(
+                    "Groups {:?} don't add up to the {} columns",
+                    groups,
+                    times.len()
+                ));
+            }
+            groups.clone()
+        }
+    };
+
+    let mut races = vec![];
+    let mut column = 0;
+
+    for size in groups {
+        let columns = column..column + size;
+        races.push(Race {
+            time: join(&times[columns.clone()])?,
+            record: join(&records[columns])?,
+        });
+        column += size;
+    }
+
+    Ok(races)
+}
+
+/// The columns of the `header:` line, which all have to be numbers.
+fn columns<'a>(input: &'a str, header: &str) -> Result<Vec<&'a str>, String> {
+    let columns: Vec<&str> = section(input, header)
+        .map_err(|e| e.to_string())?
+        .split_whitespace()
+        .collect();
+
+    match columns
+        .iter()
+        .find(|column| !column.bytes().all(|b| b.is_ascii_digit()))
+    {
+        Some(column) => Err(format!(
+            "Invalid number `{}` in the {} line",
+            column, header
+        )),
+        None => Ok(columns),
+    }
+}
+
+fn join(columns: &[&str]) -> Result<u128, String> {
+    let digits = columns.concat();
+    digits
+        .parse()
+        .map_err(|_| format!("`{}` does not fit into u128", digits))
+}
+
+/// One row per race with its winning hold times, the best hold time and the distance it gets.
+pub fn report(races: &[Race]) -> String {
+    let mut rows = vec![[
+        "race",
+        "time",
+        "record",
+        "first win",
+        "last win",
+        "best hold",
+        "best distance",
+        "ways",
+    ]
+    .map(str::to_owned)];
+
+    for (i, race) in races.iter().enumerate() {
+        let (first, last) = match race.winning_interval() {
+            Some(interval) => (interval.start().to_string(), interval.end().to_string()),
+            None => ("-".to_owned(), "-".to_owned()),
+        };
+
+        let hold = race.optimal_hold();
+        let best_hold = match race.time - hold {
+            other if other != hold => format!("{} or {}", hold, other),
+            _ => hold.to_string(),
+        };
+
+        rows.push([
+            (i + 1).to_string(),
+            race.time.to_string(),
+            race.record.to_string(),
+            first,
+            last,
+            best_hold,
+            race.distance(hold)
+                .map_or("overflow".to_owned(), |distance| distance.to_string()),
+            race.ways_to_win().to_string(),
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..8)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect();
+
+    let mut table = String::new();
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:>width$}", cell, width = width))
+            .collect();
+        table.push_str(cells.join("  ").trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+#[test]
+fn kerning_modes() {
+    let input = "Time:      7  15   30\nDistance:  9  40  200";
+
+    let races = parse_races(input, &Kerning::Separate).unwrap();
+    assert_eq!(
+        races[1],
+        Race {
+            time: 15,
+            record: 40
+        }
+    );
+    assert_eq!(races[1].winning_interval(), Some(4..=11));
+    assert_eq!(races[1].optimal_hold(), 7);
+    assert_eq!(races[1].distance(7), Some(56));
+
+    assert_eq!(
+        parse_races(input, &Kerning::Kerned),
+        Ok(vec![Race {
+            time: 71530,
+            record: 940200
+        }])
+    );
+    assert_eq!(
+        parse_races(input, &"groups=2,1".parse().unwrap()),
+        Ok(vec![
+            Race {
+                time: 715,
+                record: 940
+            },
+            Race {
+                time: 30,
+                record: 200
+            }
+        ])
+    );
+
+    // beyond u64
+    let long = "Time: 123456789 123456789 123\nDistance: 1 2 3";
+    let race = parse_races(long, &Kerning::Kerned).unwrap()[0];
+    assert_eq!(race.time, 123456789123456789123);
+    assert_eq!(race.ways_to_win(), 123456789123456789123 - 1);
+
+    assert!("groups=2,0".parse::<Kerning>().is_err());
+    assert!("glued".parse::<Kerning>().is_err());
+    assert!(parse_races(input, &Kerning::Groups(vec![1, 1])).is_err());
+    assert!(parse_races("Time: 7 15\nDistance: 9", &Kerning::Separate).is_err());
+    assert!(parse_races("Time: 7 1x\nDistance: 9 40", &Kerning::Separate).is_err());
+    let too_long = format!("Time: {}\nDistance: 1", "9 ".repeat(40));
+    assert!(parse_races(&too_long, &Kerning::Kerned).is_err());
+    assert!(parse_races("Time: 7  \nDistance: 9\n\n", &Kerning::Separate).is_ok());
+    assert!(parse_races("Time: 7\nDistance: 9 trailing", &Kerning::Separate).is_err());
+}
+
+#[test]
+fn race_report() {
+    let races = [
+        Race { time: 7, record: 9 },
+        Race {
+            time: 10,
+            record: 25,
+        },
+    ];
+
+    assert_eq!(
+        report(&races),
+        "race  time  record  first win  last win  best hold  best distance  ways\n   \
+         1     7       9          2         5     3 or 4             12     4\n   \
+         2    10      25          -         -          5             25     0\n"
+    );
+}
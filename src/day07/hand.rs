@@ -0,0 +1,162 @@
+use crate::rules::Ruleset;
+use std::cmp::Ordering;
+
+/// The type of a hand of any size: the sizes of its groups of equal cards, largest
+/// first. Types compare by their largest group, then the second largest and so on,
+/// which for five cards is the usual order from high card `[1, 1, 1, 1, 1]` over
+/// two pair `[2, 2, 1]` and full house `[3, 2]` to five of a kind `[5]`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandType {
+    groups: Vec<usize>,
+}
+
+impl HandType {
+    /// The type of a hand with groups of the given sizes, in any order.
+    pub fn from_counts(counts: &[usize]) -> Self {
+        let mut groups: Vec<usize> = counts.iter().copied().filter(|&count| count > 0).collect();
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+
+        HandType { groups }
+    }
+}
+
+/// A hand checked against a ruleset, with everything needed to order it.
+#[derive(Debug, Clone)]
+pub struct Hand {
+    pub bid: u32,
+    pub hand_type: HandType,
+    /// Strength of each card, 0 for the weakest card of the ranking.
+    pub strengths: Vec<u8>,
+    /// Index of the hand in the input, identical hands keep their input order.
+    pub position: usize,
+}
+
+impl Ord for Hand {
+    /// By type, then card by card, then by position, so no two hands of an input are equal.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.strengths.cmp(&other.strengths))
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand {}
+
+/// One `cards bid` line per hand, all cards have to be in the ranking of `rules`
+/// and every hand needs `rules.hand_size` of them. Errors mention the line number.
+pub fn parse_hands(input: &str, rules: &Ruleset) -> Result<Vec<Hand>, String> {
+    let mut hands = vec![];
+
+    for (line_nr, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let error = |message: String| format!("Line {}: {}", line_nr + 1, message);
+
+        let (cards, bid) = line
+            .split_once(' ')
+            .ok_or(error("expected `cards bid`".to_owned()))?;
+        let bid = bid
+            .trim()
+            .parse()
+            .map_err(|_| error(format!("invalid bid `{}`", bid.trim())))?;
+
+        let strengths: Vec<u8> = cards
+            .chars()
+            .map(|card| {
+                rules
+                    .strength(card)
+                    .ok_or(error(format!("card `{}` is not in the ranking", card)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        if strengths.len() != rules.hand_size {
+            return Err(error(format!(
+                "expected {} cards, found {}",
+                rules.hand_size,
+                strengths.len()
+            )));
+        }
+
+        hands.push(Hand {
+            bid,
+            hand_type: rules.classify(cards),
+            strengths,
+            position: hands.len(),
+        });
+    }
+
+    Ok(hands)
+}
+
+/// Sum of each bid times the rank of its hand, the weakest hand having rank 1.
+pub fn total_winnings(hands: &[Hand]) -> u64 {
+    let mut ranked: Vec<&Hand> = hands.iter().collect();
+    ranked.sort_unstable();
+
+    ranked
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| (i as u64 + 1) * hand.bid as u64)
+        .sum()
+}
+
+#[test]
+fn ordering() {
+    let rules = Ruleset::part1();
+    let hands = parse_hands(
+        "
+        KK677 1
+        2345A 2
+        KK677 3
+        KTJJT 4
+        2345A 5
+        ",
+        &rules,
+    )
+    .unwrap();
+
+    let mut sorted = hands.clone();
+    sorted.sort_unstable();
+    let bids: Vec<u32> = sorted.iter().map(|hand| hand.bid).collect();
+    // identical hands in input order
+    assert_eq!(bids, vec![2, 5, 4, 1, 3]);
+    assert_ne!(hands[0], hands[2]);
+    assert_eq!(hands[0].cmp(&hands[2]), Ordering::Less);
+    assert_eq!(total_winnings(&hands), 2 + 2 * 5 + 3 * 4 + 4 + 5 * 3);
+
+    // A is 12, 2 is 0
+    assert_eq!(hands[3].strengths, vec![11, 8, 9, 9, 8]);
+}
+
+#[test]
+fn invalid_hands() {
+    let rules = Ruleset::part1();
+
+    assert_eq!(
+        parse_hands("32T3K 765\nkk677 28", &rules),
+        Err("Line 2: card `k` is not in the ranking".to_owned())
+    );
+    assert_eq!(
+        parse_hands("32T3 765", &rules),
+        Err("Line 1: expected 5 cards, found 4".to_owned())
+    );
+    assert!(parse_hands("32T3K", &rules).is_err());
+    assert!(parse_hands("32T3K -1", &rules).is_err());
+    assert!(parse_hands("32T3K 7 65", &rules).is_err());
+}
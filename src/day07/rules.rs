@@ -0,0 +1,346 @@
+use crate::hand::HandType;
+use std::{collections::HashSet, error::Error, fs, str::FromStr};
+
+/// How the cards of a hand are grouped to find its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    /// Only identical cards form groups, wild cards count as themselves (part 1).
+    Plain,
+    /// The wild cards join the largest group of the other cards (part 2).
+    WildJoinsLargest,
+    /// Tries every other card of the ranking for every wild card and keeps the best
+    /// type. Slow, but gives the same types as `WildJoinsLargest`.
+    BestSubstitution,
+}
+
+impl FromStr for Classification {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Classification::Plain),
+            "wild-joins-largest" => Ok(Classification::WildJoinsLargest),
+            "best-substitution" => Ok(Classification::BestSubstitution),
+            _ => Err(format!("Unknown classification `{}`", s)),
+        }
+    }
+}
+
+/// The house rules of a game of Camel Cards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruleset {
+    /// All cards of the game, the strongest first.
+    pub ranking: Vec<char>,
+    pub wild: Vec<char>,
+    pub classification: Classification,
+    /// Cards per hand.
+    pub hand_size: usize,
+}
+
+impl Ruleset {
+    pub fn part1() -> Self {
+        Ruleset {
+            ranking: "AKQJT98765432".chars().collect(),
+            wild: vec![],
+            classification: Classification::Plain,
+            hand_size: 5,
+        }
+    }
+
+    /// `J` is a joker, the weakest card on its own but wild when classifying.
+    pub fn part2() -> Self {
+        Ruleset {
+            ranking: "AKQT98765432J".chars().collect(),
+            wild: vec!['J'],
+            classification: Classification::WildJoinsLargest,
+            hand_size: 5,
+        }
+    }
+
+    /// Reads `key = value` lines changing the part 1 rules, empty lines and lines
+    /// starting with `#` are ignored. Wild cards without a classification join the
+    /// largest group, wild cards with `plain` are rejected:
+    ///
+    /// ```text
+    /// ranking = AKQT98765432J
+    /// wild = J
+    /// classification = wild-joins-largest
+    /// hand_size = 5
+    /// ```
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::parse(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rules = Ruleset::part1();
+        let mut classification = None;
+
+        for (line_nr, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: String| format!("Line {}: {}", line_nr + 1, message);
+
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or(error(format!("expected `key = value`, found `{}`", line)))?;
+
+            match key {
+                "ranking" => rules.ranking = value.chars().collect(),
+                "wild" => rules.wild = value.chars().collect(),
+                "classification" => classification = Some(value.parse().map_err(error)?),
+                "hand_size" => {
+                    rules.hand_size = match value.parse() {
+                        Ok(0) | Err(_) => {
+                            return Err(error(format!("invalid hand size `{}`", value)))
+                        }
+                        Ok(size) => size,
+                    }
+                }
+                _ => return Err(error(format!("unknown key `{}`", key))),
+            }
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(card) = rules.ranking.iter().find(|&&card| !seen.insert(card)) {
+            return Err(format!("Card `{}` appears twice in the ranking", card));
+        }
+        // strengths are stored as u8
+        if rules.ranking.is_empty() || rules.ranking.len() > 256 {
+            return Err(format!(
+                "Expected 1 to 256 cards, found {}",
+                rules.ranking.len()
+            ));
+        }
+
+        if let Some(card) = rules.wild.iter().find(|card| !rules.ranking.contains(card)) {
+            return Err(format!("Wild card `{}` is not in the ranking", card));
+        }
+
+        rules.classification = match classification {
+            Some(Classification::Plain) if !rules.wild.is_empty() => {
+                return Err("Wild cards need a classification other than plain".to_owned())
+            }
+            Some(classification) => classification,
+            None if !rules.wild.is_empty() => Classification::WildJoinsLargest,
+            None => Classification::Plain,
+        };
+
+        Ok(rules)
+    }
+
+    /// Position in the ranking counted from the weakest card, `None` for unknown cards.
+    pub fn strength(&self, card: char) -> Option<u8> {
+        self.ranking
+            .iter()
+            .rev()
+            .position(|&ranked| ranked == card)
+            .map(|strength| strength as u8)
+    }
+
+    /// The best type the cards can make, wild cards standing for any other card.
+    pub fn classify(&self, cards: &str) -> HandType {
+        let mut cards: Vec<char> = cards.chars().collect();
+
+        match self.classification {
+            Classification::Plain => HandType::from_counts(&group_sizes(&cards)),
+            Classification::WildJoinsLargest => {
+                let (wild, other): (Vec<char>, Vec<char>) =
+                    cards.iter().partition(|card| self.wild.contains(card));
+
+                // adding to the largest group beats everything else, as types compare
+                // by their largest group first; a hand of only wild cards is one group
+                let mut counts = group_sizes(&other);
+                counts.sort_unstable_by(|a, b| b.cmp(a));
+                match counts.first_mut() {
+                    Some(largest) => *largest += wild.len(),
+                    None => counts.push(wild.len()),
+                }
+
+                HandType::from_counts(&counts)
+            }
+            Classification::BestSubstitution => self.best_substitution(&mut cards, 0),
+        }
+    }
+
+    /// Best type with the wild cards from index `from` on replaced by other cards.
+    fn best_substitution(&self, cards: &mut [char], from: usize) -> HandType {
+        let Some(i) = (from..cards.len()).find(|&i| self.wild.contains(&cards[i])) else {
+            return HandType::from_counts(&group_sizes(cards));
+        };
+
+        let wild = cards[i];
+        let substitutes: Vec<char> = self
+            .ranking
+            .iter()
+            .copied()
+            .filter(|card| !self.wild.contains(card))
+            .collect();
+
+        // without other cards the wild cards can only be themselves
+        if substitutes.is_empty() {
+            return HandType::from_counts(&group_sizes(cards));
+        }
+
+        let best = substitutes
+            .into_iter()
+            .map(|substitute| {
+                cards[i] = substitute;
+                self.best_substitution(cards, i + 1)
+            })
+            .max();
+        cards[i] = wild;
+
+        best.expect("there is at least one substitute")
+    }
+}
+
+/// How often each distinct card appears.
+fn group_sizes(cards: &[char]) -> Vec<usize> {
+    let mut counts: Vec<(char, usize)> = vec![];
+
+    for &card in cards {
+        match counts.iter_mut().find(|(counted, _)| *counted == card) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((card, 1)),
+        }
+    }
+
+    counts.into_iter().map(|(_, count)| count).collect()
+}
+
+#[test]
+fn house_rules() {
+    let rules = Ruleset::parse(
+        "# jokers and deuces are wild
+        ranking = AKQT98765432J
+        wild = J2
+        classification = wild-joins-largest",
+    )
+    .unwrap();
+
+    // three of a kind, five of a kind and a full house without wild cards
+    assert_eq!(rules.classify("J2345"), HandType::from_counts(&[3, 1, 1]));
+    assert_eq!(rules.classify("JJ2J2"), HandType::from_counts(&[5]));
+    assert_eq!(
+        Ruleset::part1().classify("JJ2J2"),
+        HandType::from_counts(&[2, 3])
+    );
+    assert_eq!(rules.strength('J'), Some(0));
+    assert_eq!(rules.strength('A'), Some(12));
+    assert_eq!(rules.strength('x'), None);
+
+    assert_eq!(
+        Ruleset::parse("ranking = AKQ\nwild: K"),
+        Err("Line 2: expected `key = value`, found `wild: K`".to_owned())
+    );
+    assert!(Ruleset::parse("wild = X").is_err());
+    assert!(Ruleset::parse("classification = best").is_err());
+    assert!(Ruleset::parse("jokers = J").is_err());
+    assert!(Ruleset::parse("ranking = AKA").is_err());
+    assert!(Ruleset::parse("ranking =").is_err());
+    assert!(Ruleset::parse("hand_size = 0").is_err());
+    assert_eq!(Ruleset::parse("hand_size = 3").unwrap().hand_size, 3);
+
+    // wild cards are never silently plain
+    assert_eq!(
+        Ruleset::parse("wild = J").unwrap().classification,
+        Classification::WildJoinsLargest
+    );
+    assert_eq!(
+        Ruleset::parse("wild = J\nclassification = plain"),
+        Err("Wild cards need a classification other than plain".to_owned())
+    );
+    assert_eq!(
+        Ruleset::parse("wild =\nclassification = plain")
+            .unwrap()
+            .classification,
+        Classification::Plain
+    );
+}
+
+#[test]
+fn wild_cards() {
+    let rules = Ruleset::part2();
+    assert_eq!(rules.classify("JJJJJ"), HandType::from_counts(&[5]));
+    assert_eq!(rules.classify("JJJJ2"), HandType::from_counts(&[5]));
+    assert_eq!(rules.classify("JJ234"), HandType::from_counts(&[3, 1, 1]));
+    assert_eq!(rules.classify("J2233"), HandType::from_counts(&[3, 2]));
+    assert_eq!(
+        rules.classify("J2345"),
+        HandType::from_counts(&[2, 1, 1, 1])
+    );
+    assert_eq!(rules.classify("J"), HandType::from_counts(&[1]));
+    assert_eq!(
+        rules.classify("JJ22334455"),
+        HandType::from_counts(&[4, 2, 2, 2])
+    );
+    assert!(rules.classify("333444") < rules.classify("J33344"));
+
+    // every five card hand up to the order of its cards, with up to five jokers
+    let brute_force = Ruleset {
+        classification: Classification::BestSubstitution,
+        ..Ruleset::part2()
+    };
+    let mut hands = vec![String::new()];
+    for _ in 0..5 {
+        hands = hands
+            .iter()
+            .flat_map(|hand| {
+                let last = hand.chars().last();
+                rules
+                    .ranking
+                    .iter()
+                    .skip_while(move |&&card| last.is_some_and(|last| last != card))
+                    .map(move |card| format!("{}{}", hand, card))
+            })
+            .collect();
+    }
+    assert_eq!(hands.len(), 6188);
+
+    for hand in &hands {
+        assert_eq!(rules.classify(hand), brute_force.classify(hand), "{}", hand);
+    }
+}
+
+#[test]
+fn hand_sizes() {
+    // every hand of up to six cards in every order, X and Y being wild
+    let fast = Ruleset {
+        ranking: "ABCXY".chars().collect(),
+        wild: vec!['X', 'Y'],
+        classification: Classification::WildJoinsLargest,
+        hand_size: 6,
+    };
+    let brute_force = Ruleset {
+        classification: Classification::BestSubstitution,
+        ..fast.clone()
+    };
+
+    let mut hands = vec![String::new()];
+    for _ in 0..6 {
+        hands = hands
+            .iter()
+            .flat_map(|hand| {
+                fast.ranking
+                    .iter()
+                    .map(move |card| format!("{}{}", hand, card))
+            })
+            .collect();
+
+        for hand in &hands {
+            assert_eq!(fast.classify(hand), brute_force.classify(hand), "{}", hand);
+        }
+    }
+
+    // wild cards only stand for themselves if there is nothing else
+    let only_wild = Ruleset {
+        ranking: vec!['J'],
+        wild: vec!['J'],
+        ..brute_force
+    };
+    assert_eq!(only_wild.classify("JJJ"), HandType::from_counts(&[3]));
+}
@@ -1,10 +1,11 @@
-use regex::{Match, Matches, Regex};
+use advent_of_code_2023::memo::Memo;
+use regex::Regex;
 use reqwest::header::COOKIE;
 use std::{error::Error, fs};
 
 fn main() -> Result<(), Box<dyn Error>> {
     // get day
-    let bin_name = std::env::args().nth(0).unwrap();
+    let bin_name = std::env::args().next().unwrap();
     let day = Regex::new(r"day(\d+)")
         .unwrap()
         .captures(&bin_name)
@@ -35,12 +36,12 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // solve problems
     part1(&input);
-    // part2(&input);
+    part2(&input);
 
     Ok(())
 }
 
-fn part1(input: &str) -> usize {
+fn part1(input: &str) -> u64 {
     let input = input.trim();
     let mut overall_sum = 0;
 
@@ -48,13 +49,10 @@ fn part1(input: &str) -> usize {
         let line = line.trim();
 
         // parse line
-        let (springs, nums_str) = line.split_once(" ").unwrap();
-        let nums: Vec<usize> = nums_str.split(",").map(|x| x.parse().unwrap()).collect();
+        let (springs, nums_str) = line.split_once(' ').unwrap();
+        let nums: Vec<usize> = nums_str.split(',').map(|x| x.parse().unwrap()).collect();
 
-        // find every valid combination while replacing question marks
-        let combinations = create_valid_combinations(vec![springs.to_owned()], &nums);
-
-        overall_sum += combinations.len();
+        overall_sum += count_arrangements(springs, &nums);
     }
 
     println!("The answer to part 1 is: {}", overall_sum);
@@ -62,53 +60,64 @@ fn part1(input: &str) -> usize {
     overall_sum
 }
 
-fn create_valid_combinations(v: Vec<String>, nums: &Vec<usize>) -> Vec<String> {
-    if v[0].contains('?') {
-        let v2 = v
-            .iter()
-            .flat_map(|s| {
-                let mut cands: Vec<String> = vec![];
-                let cand1 = s.replacen("?", "#", 1);
-                let cand2 = s.replacen("?", ".", 1);
-
-                if check_for_plausibility(&cand1, nums) {
-                    cands.push(cand1);
-                }
+fn part2(input: &str) -> u64 {
+    let input = input.trim();
+    let mut overall_sum = 0;
 
-                if check_for_plausibility(&cand2, nums) {
-                    cands.push(cand2);
-                }
+    for line in input.lines() {
+        let line = line.trim();
 
-                cands
-            })
-            .collect();
-        return create_valid_combinations(v2, nums);
-    } else {
-        return v;
-    };
-}
+        // parse line
+        let (springs, nums_str) = line.split_once(' ').unwrap();
+        let nums: Vec<usize> = nums_str.split(',').map(|x| x.parse().unwrap()).collect();
 
-fn check_for_plausibility(s: &str, nums: &Vec<usize>) -> bool {
-    let mut question_mark_index = s.find('?').unwrap_or(s.len());
-    if question_mark_index != s.len() {
-        question_mark_index = s[0..question_mark_index]
-            .rfind('.')
-            .unwrap_or(0);
-    }
-    let re = Regex::new(r"#+").unwrap();
-    let matches: Vec<Match> = re.find_iter(&s[0..question_mark_index]).collect();
+        // unfold: five copies of the springs separated by '?', five copies of the numbers
+        let springs = [springs; 5].join("?");
+        let nums = nums.repeat(5);
 
-    if question_mark_index == s.len() && matches.len() != nums.len() {
-        return false;
+        overall_sum += count_arrangements(&springs, &nums);
     }
 
-    for (spring_group, num) in matches.iter().zip(nums) {
-        if spring_group.len() != *num {
-            return false;
-        }
-    }
+    println!("The answer to part 2 is: {}", overall_sum);
+
+    overall_sum
+}
+
+fn count_arrangements(springs: &str, nums: &[usize]) -> u64 {
+    let springs = springs.as_bytes();
+
+    // key is (position in springs, index of current group, length of current run of '#')
+    let mut memo = Memo::new(
+        |recurse: &mut dyn FnMut((usize, usize, usize)) -> u64,
+         (pos, group, run): (usize, usize, usize)| {
+            // reached the end, valid if all groups are closed or the last run closes the last group
+            if pos == springs.len() {
+                let all_closed = group == nums.len() && run == 0;
+                let last_closes = group + 1 == nums.len() && nums[group] == run;
+                return u64::from(all_closed || last_closes);
+            }
+
+            let mut count = 0;
+
+            // treat as damaged: extend the current run, if the group allows it
+            if matches!(springs[pos], b'#' | b'?') && group < nums.len() && run < nums[group] {
+                count += recurse((pos + 1, group, run + 1));
+            }
+
+            // treat as operational: only possible outside of a run or if the run completes the group
+            if matches!(springs[pos], b'.' | b'?') {
+                if run == 0 {
+                    count += recurse((pos + 1, group, 0));
+                } else if run == nums[group] {
+                    count += recurse((pos + 1, group + 1, 0));
+                }
+            }
+
+            count
+        },
+    );
 
-    true
+    memo.get((0, 0, 0))
 }
 
 #[test]
@@ -123,4 +132,5 @@ fn examples() {
     ";
 
     assert_eq!(part1(example_input), 21);
+    assert_eq!(part2(example_input), 525152);
 }
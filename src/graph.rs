@@ -0,0 +1,542 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+    ops::Index,
+};
+
+/// A graph, given by the neighbors of each node.
+///
+/// Implemented for explicit adjacency maps, [`WeightedGraph`], [`Grid`] and
+/// closures via [`from_fn`].
+pub trait Neighbors {
+    type Node: Clone + Eq + Hash;
+
+    fn neighbors(&self, node: &Self::Node) -> Vec<Self::Node>;
+
+    /// Weight of the edge `from` -> `to`, used by [`dijkstra`] and [`astar`].
+    fn cost(&self, _from: &Self::Node, _to: &Self::Node) -> u64 {
+        1
+    }
+}
+
+impl<N: Clone + Eq + Hash> Neighbors for HashMap<N, Vec<N>> {
+    type Node = N;
+
+    fn neighbors(&self, node: &N) -> Vec<N> {
+        self.get(node).cloned().unwrap_or_default()
+    }
+}
+
+/// Adjacency map with edge weights.
+pub struct WeightedGraph<N>(pub HashMap<N, Vec<(N, u64)>>);
+
+impl<N: Clone + Eq + Hash> Neighbors for WeightedGraph<N> {
+    type Node = N;
+
+    fn neighbors(&self, node: &N) -> Vec<N> {
+        self.0
+            .get(node)
+            .map(|edges| edges.iter().map(|(n, _)| n.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn cost(&self, from: &N, to: &N) -> u64 {
+        self.0[from]
+            .iter()
+            .filter(|(n, _)| n == to)
+            .map(|(_, cost)| *cost)
+            .min()
+            .unwrap()
+    }
+}
+
+/// Graph given by a function returning the neighbors of a node.
+pub struct FromFn<N, F> {
+    f: F,
+    _marker: PhantomData<N>,
+}
+
+pub fn from_fn<N, F>(f: F) -> FromFn<N, F>
+where
+    N: Clone + Eq + Hash,
+    F: Fn(&N) -> Vec<N>,
+{
+    FromFn {
+        f,
+        _marker: PhantomData,
+    }
+}
+
+impl<N, F> Neighbors for FromFn<N, F>
+where
+    N: Clone + Eq + Hash,
+    F: Fn(&N) -> Vec<N>,
+{
+    type Node = N;
+
+    fn neighbors(&self, node: &N) -> Vec<N> {
+        (self.f)(node)
+    }
+}
+
+/// Position in a [`Grid`] as (y, x).
+pub type Pos = (usize, usize);
+
+/// Rectangular grid, as a graph every cell is connected to its 4 neighbors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    pub cells: Vec<Vec<T>>,
+}
+
+impl Grid<char> {
+    /// One row per (trimmed, non-empty) line of `input`.
+    pub fn parse(input: &str) -> Self {
+        Grid {
+            cells: input
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .map(|line| line.chars().collect())
+                .collect(),
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.first().map_or(0, |row| row.len())
+    }
+
+    pub fn get(&self, (y, x): Pos) -> Option<&T> {
+        self.cells.get(y)?.get(x)
+    }
+
+    /// Position of the first cell (row by row) equal to `value`.
+    pub fn position(&self, value: &T) -> Option<Pos> {
+        self.cells
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| row.iter().position(|c| c == value).map(|x| (y, x)))
+    }
+
+    /// `pos` moved by (dy, dx), if still inside the grid.
+    pub fn offset(&self, (y, x): Pos, (dy, dx): (isize, isize)) -> Option<Pos> {
+        let new_pos = (y.checked_add_signed(dy)?, x.checked_add_signed(dx)?);
+        self.get(new_pos).map(|_| new_pos)
+    }
+
+    /// The up to 4 orthogonal neighbors of `pos` inside the grid.
+    pub fn adjacent(&self, pos: Pos) -> Vec<Pos> {
+        [(-1, 0), (0, 1), (1, 0), (0, -1)]
+            .into_iter()
+            .filter_map(|d| self.offset(pos, d))
+            .collect()
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (y, x): Pos) -> &T {
+        &self.cells[y][x]
+    }
+}
+
+impl<T: PartialEq> Neighbors for Grid<T> {
+    type Node = Pos;
+
+    fn neighbors(&self, pos: &Pos) -> Vec<Pos> {
+        self.adjacent(*pos)
+    }
+}
+
+/// Number of edges from `start` to every reachable node.
+pub fn bfs<G: Neighbors>(graph: &G, start: G::Node) -> HashMap<G::Node, usize> {
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node];
+
+        for next in graph.neighbors(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Cost of the cheapest path from `start` to every reachable node.
+pub fn dijkstra<G: Neighbors>(graph: &G, start: G::Node) -> HashMap<G::Node, u64> {
+    let mut distances: HashMap<G::Node, u64> = HashMap::new();
+
+    // heap only holds indices into `nodes`, so nodes don't have to be `Ord`
+    let mut nodes = vec![start];
+    let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        let node = &nodes[index];
+        if distances.contains_key(node) {
+            continue;
+        }
+        distances.insert(node.clone(), cost);
+
+        for next in graph.neighbors(node) {
+            if !distances.contains_key(&next) {
+                let next_cost = cost + graph.cost(&nodes[index], &next);
+                heap.push(Reverse((next_cost, nodes.len())));
+                nodes.push(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Cheapest path from `start` to a node satisfying `is_goal`, as (cost, path including start and goal).
+///
+/// `heuristic` must never overestimate the remaining cost, otherwise the path is not guaranteed to be optimal.
+/// It doesn't have to be consistent, nodes are expanded again if a cheaper way to them turns up.
+pub fn astar<G, P, H>(
+    graph: &G,
+    start: G::Node,
+    is_goal: P,
+    heuristic: H,
+) -> Option<(u64, Vec<G::Node>)>
+where
+    G: Neighbors,
+    P: Fn(&G::Node) -> bool,
+    H: Fn(&G::Node) -> u64,
+{
+    let mut best_costs: HashMap<G::Node, u64> = HashMap::from([(start.clone(), 0)]);
+    let mut came_from: HashMap<G::Node, G::Node> = HashMap::new();
+
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut nodes = vec![start];
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        // a cheaper way to the node was found after this entry was pushed
+        if cost > best_costs[&node] {
+            continue;
+        }
+
+        if is_goal(&node) {
+            // walk back to start
+            let mut path = vec![node];
+            while let Some(prev) = came_from.get(path.last().unwrap()) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+
+        for next in graph.neighbors(&node) {
+            let next_cost = cost + graph.cost(&node, &next);
+
+            if best_costs.get(&next).is_none_or(|&best| next_cost < best) {
+                best_costs.insert(next.clone(), next_cost);
+                came_from.insert(next.clone(), node.clone());
+                heap.push(Reverse((
+                    next_cost + heuristic(&next),
+                    next_cost,
+                    nodes.len(),
+                )));
+                nodes.push(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// [`astar`] without heuristic.
+pub fn shortest_path<G, P>(graph: &G, start: G::Node, is_goal: P) -> Option<(u64, Vec<G::Node>)>
+where
+    G: Neighbors,
+    P: Fn(&G::Node) -> bool,
+{
+    astar(graph, start, is_goal, |_| 0)
+}
+
+/// Depth first search over everything reachable from `starts`, returns the nodes in
+/// post-order or the first (directed) cycle found.
+fn dfs_post_order<G, I>(graph: &G, starts: I) -> Result<Vec<G::Node>, Vec<G::Node>>
+where
+    G: Neighbors,
+    I: IntoIterator<Item = G::Node>,
+{
+    // false while the node is on the stack, true once all its descendants are done
+    let mut done: HashMap<G::Node, bool> = HashMap::new();
+    let mut order = vec![];
+
+    for start in starts {
+        if done.contains_key(&start) {
+            continue;
+        }
+
+        done.insert(start.clone(), false);
+        let neighbors = graph.neighbors(&start).into_iter();
+        let mut stack = vec![(start, neighbors)];
+
+        while let Some((_, neighbors)) = stack.last_mut() {
+            let Some(next) = neighbors.next() else {
+                let (node, _) = stack.pop().unwrap();
+                done.insert(node.clone(), true);
+                order.push(node);
+                continue;
+            };
+
+            match done.get(&next) {
+                None => {
+                    done.insert(next.clone(), false);
+                    let neighbors = graph.neighbors(&next).into_iter();
+                    stack.push((next, neighbors));
+                }
+                // back edge to a node on the stack
+                Some(false) => {
+                    let cycle_start = stack.iter().position(|(n, _)| *n == next).unwrap();
+                    return Err(stack.drain(cycle_start..).map(|(n, _)| n).collect());
+                }
+                Some(true) => {}
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// A directed cycle reachable from `start`, in edge order.
+///
+/// Every edge counts, so in a graph whose edges go both ways this finds two neighbors,
+/// use [`find_undirected_cycle`] there.
+pub fn find_cycle<G: Neighbors>(graph: &G, start: G::Node) -> Option<Vec<G::Node>> {
+    dfs_post_order(graph, [start]).err()
+}
+
+/// A cycle reachable from `start` in a graph whose edges go both ways, in edge order.
+///
+/// Going straight back along the edge a node was reached by does not count, so the
+/// cycle has at least 3 nodes.
+pub fn find_undirected_cycle<G: Neighbors>(graph: &G, start: G::Node) -> Option<Vec<G::Node>> {
+    // true while the node is on the stack
+    let mut on_stack: HashMap<G::Node, bool> = HashMap::from([(start.clone(), true)]);
+    let neighbors = graph.neighbors(&start).into_iter();
+    let mut stack = vec![(start, neighbors)];
+
+    while let Some((_, neighbors)) = stack.last_mut() {
+        let Some(next) = neighbors.next() else {
+            let (node, _) = stack.pop().unwrap();
+            on_stack.insert(node, false);
+            continue;
+        };
+
+        // the edge back to the node we came from
+        if stack.len() >= 2 && stack[stack.len() - 2].0 == next {
+            continue;
+        }
+
+        match on_stack.get(&next) {
+            None => {
+                on_stack.insert(next.clone(), true);
+                let neighbors = graph.neighbors(&next).into_iter();
+                stack.push((next, neighbors));
+            }
+            Some(true) => {
+                let cycle_start = stack.iter().position(|(n, _)| *n == next).unwrap();
+                return Some(stack.drain(cycle_start..).map(|(n, _)| n).collect());
+            }
+            // finished nodes were fully explored, any cycle through them was found then
+            Some(false) => {}
+        }
+    }
+
+    None
+}
+
+/// All nodes reachable from `starts`, ordered so that every edge points forward.
+/// Fails with a cycle if there is none.
+pub fn topological_sort<G, I>(graph: &G, starts: I) -> Result<Vec<G::Node>, Vec<G::Node>>
+where
+    G: Neighbors,
+    I: IntoIterator<Item = G::Node>,
+{
+    dfs_post_order(graph, starts).map(|mut order| {
+        order.reverse();
+        order
+    })
+}
+
+/// Groups `nodes` (and everything reachable from them) into components,
+/// edges are expected to go both ways.
+pub fn connected_components<G, I>(graph: &G, nodes: I) -> Vec<Vec<G::Node>>
+where
+    G: Neighbors,
+    I: IntoIterator<Item = G::Node>,
+{
+    let mut seen: HashSet<G::Node> = HashSet::new();
+    let mut components = vec![];
+
+    for node in nodes {
+        if seen.contains(&node) {
+            continue;
+        }
+
+        let component: Vec<G::Node> = bfs(graph, node).into_keys().collect();
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+
+    components
+}
+
+#[test]
+fn grid_search() {
+    let grid = Grid::parse(
+        "
+        ..#.
+        ..#.
+        ....
+        ",
+    );
+
+    // walls are no neighbors
+    let open = from_fn(|&pos: &Pos| {
+        grid.adjacent(pos)
+            .into_iter()
+            .filter(|&p| grid.get(p) == Some(&'.'))
+            .collect()
+    });
+
+    let distances = bfs(&open, (0, 0));
+    assert_eq!(distances[&(0, 3)], 7);
+    assert_eq!(distances.len(), 10);
+
+    let manhattan = |&(y, x): &Pos| (y.abs_diff(0) + x.abs_diff(3)) as u64;
+    let (cost, path) = astar(&open, (0, 0), |&p| p == (0, 3), manhattan).unwrap();
+    assert_eq!(cost, 7);
+    assert_eq!(path.len(), 8);
+    assert_eq!(path.first(), Some(&(0, 0)));
+
+    assert_eq!(connected_components(&open, [(0, 0), (2, 3)]).len(), 1);
+    assert_eq!(bfs(&grid, (0, 0)).len(), 12);
+}
+
+#[test]
+fn weighted() {
+    let graph = WeightedGraph(HashMap::from([
+        ('a', vec![('b', 7), ('c', 2)]),
+        ('c', vec![('b', 3), ('d', 10)]),
+        ('b', vec![('d', 1)]),
+    ]));
+
+    let distances = dijkstra(&graph, 'a');
+    assert_eq!(
+        distances,
+        HashMap::from([('a', 0), ('c', 2), ('b', 5), ('d', 6)])
+    );
+
+    let (cost, path) = shortest_path(&graph, 'a', |&n| n == 'd').unwrap();
+    assert_eq!((cost, path), (6, vec!['a', 'c', 'b', 'd']));
+    assert_eq!(shortest_path(&graph, 'd', |&n| n == 'a'), None);
+}
+
+#[test]
+fn cycles_and_order() {
+    let mut graph = HashMap::from([
+        ("shirt", vec!["tie", "belt"]),
+        ("tie", vec!["jacket"]),
+        ("pants", vec!["shoes", "belt"]),
+        ("belt", vec!["jacket"]),
+    ]);
+
+    let order = topological_sort(&graph, ["shirt", "pants"]).unwrap();
+    let index = |n| order.iter().position(|&x| x == n).unwrap();
+    for (from, tos) in &graph {
+        for to in tos {
+            assert!(index(*from) < index(*to));
+        }
+    }
+    assert_eq!(find_cycle(&graph, "shirt"), None);
+
+    graph.insert("jacket", vec!["shirt"]);
+    let cycle = find_cycle(&graph, "pants").unwrap();
+    assert_eq!(cycle.len(), 3);
+    assert!(cycle.contains(&"jacket") && cycle.contains(&"shirt"));
+    assert!(topological_sort(&graph, ["pants"]).is_err());
+}
+
+#[test]
+fn undirected_cycles() {
+    let square = HashMap::from([
+        ('a', vec!['b', 'd']),
+        ('b', vec!['a', 'c']),
+        ('c', vec!['b', 'd']),
+        ('d', vec!['c', 'a']),
+    ]);
+    let cycle = find_undirected_cycle(&square, 'a').unwrap();
+    assert_eq!(cycle.len(), 4);
+
+    let path = HashMap::from([('a', vec!['b']), ('b', vec!['a', 'c']), ('c', vec!['b'])]);
+    assert_eq!(find_undirected_cycle(&path, 'a'), None);
+
+    let grid = Grid::parse(
+        "
+        ...
+        ...
+        ",
+    );
+    let cycle = find_undirected_cycle(&grid, (0, 0)).unwrap();
+    assert_eq!(cycle.len(), 4);
+    for (i, &pos) in cycle.iter().enumerate() {
+        let next = cycle[(i + 1) % cycle.len()];
+        assert!(grid.adjacent(pos).contains(&next));
+    }
+
+    // only a dead end hangs off the loop
+    let grid = Grid::parse(
+        "
+        ...#
+        ..##
+        ",
+    );
+    let open = from_fn(|&pos: &Pos| {
+        grid.adjacent(pos)
+            .into_iter()
+            .filter(|&p| grid.get(p) == Some(&'.'))
+            .collect()
+    });
+    let cycle = find_undirected_cycle(&open, (0, 2)).unwrap();
+    assert_eq!(cycle.len(), 4);
+    assert!(!cycle.contains(&(0, 2)));
+}
+
+#[test]
+fn inconsistent_heuristic() {
+    // the heuristic never overestimates, but drops by more than the edge cost from
+    // `a` to `c`, so `c` is expanded the expensive way first and has to be expanded again
+    let graph = WeightedGraph(HashMap::from([
+        ('s', vec![('a', 1), ('b', 1)]),
+        ('a', vec![('c', 1)]),
+        ('b', vec![('c', 3)]),
+        ('c', vec![('g', 3)]),
+    ]));
+    let heuristic = |node: &char| match node {
+        's' => 5,
+        'a' => 4,
+        'b' => 1,
+        _ => 0,
+    };
+
+    let (cost, path) = astar(&graph, 's', |&n| n == 'g', heuristic).unwrap();
+    assert_eq!((cost, path), (5, vec!['s', 'a', 'c', 'g']));
+}
@@ -0,0 +1,6 @@
+pub mod aho_corasick;
+pub mod graph;
+pub mod math;
+pub mod memo;
+pub mod parsing;
+pub mod polynomial;
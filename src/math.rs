@@ -0,0 +1,79 @@
+use std::ops::RangeInclusive;
+
+/// All integer `t` in `0..=time` with `t * (time - t) > distance`.
+///
+/// The winning times lie strictly between the roots of `t² - time * t + distance`,
+/// so the interval is computed from the integer square root of the discriminant
+/// instead of trying every `t`. `None` if no `t` wins.
+pub fn winning_interval(time: u128, distance: u128) -> Option<RangeInclusive<u128>> {
+    // an overflowing product is certainly larger than any u128 distance
+    let wins = |t: u128| t.checked_mul(time - t).is_none_or(|d| d > distance);
+
+    // the product is largest in the middle, if that doesn't win nothing does
+    if !wins(time / 2) {
+        return None;
+    }
+
+    // estimate the first winning time from the smaller root (time - sqrt(time² - 4 distance)) / 2
+    let discriminant = time
+        .checked_mul(time)
+        .zip(distance.checked_mul(4))
+        .map(|(time_sq, distance_4)| time_sq - distance_4);
+
+    let mut first = match discriminant {
+        Some(discriminant) => (time - discriminant.isqrt()) / 2,
+        // time² does not fit into u128, binary search the boundary instead
+        None => {
+            let (mut lo, mut hi) = (0, time / 2);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if wins(mid) {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+            lo
+        }
+    };
+
+    // rounding of the square root can put the estimate off by one
+    while first > 0 && wins(first - 1) {
+        first -= 1;
+    }
+    while !wins(first) {
+        first += 1;
+    }
+
+    // symmetric around time / 2
+    Some(first..=time - first)
+}
+
+#[test]
+fn winning_intervals() {
+    assert_eq!(winning_interval(7, 9), Some(2..=5));
+    assert_eq!(winning_interval(15, 40), Some(4..=11));
+    // 10 * 20 = 200 only ties the record
+    assert_eq!(winning_interval(30, 200), Some(11..=19));
+    assert_eq!(winning_interval(71530, 940200), Some(14..=71516));
+    assert_eq!(winning_interval(10, 25), None);
+    assert_eq!(winning_interval(0, 0), None);
+
+    // compare with trying every hold time
+    for time in 0..60 {
+        for distance in 0..1000 {
+            let winning: Vec<u128> = (0..=time).filter(|t| t * (time - t) > distance).collect();
+            let expected = winning
+                .first()
+                .map(|&first| first..=*winning.last().unwrap());
+            assert_eq!(winning_interval(time, distance), expected);
+        }
+    }
+
+    // time² overflows u128
+    let time = 1 << 100;
+    let interval = winning_interval(time, u128::MAX).unwrap();
+    let first = *interval.start();
+    assert!(first.checked_mul(time - first).is_none());
+    assert!((first - 1).checked_mul(time - first + 1).is_some());
+}
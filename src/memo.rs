@@ -0,0 +1,133 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Memoizes a recursive function.
+///
+/// The function gets a `recurse` callback as first argument, which must be used
+/// for the recursive calls so their results end up in the cache, e.g.
+///
+/// ```
+/// use advent_of_code_2023::memo::Memo;
+///
+/// let mut fib = Memo::new(|recurse: &mut dyn FnMut(u64) -> u64, n: u64| {
+///     if n < 2 {
+///         n
+///     } else {
+///         recurse(n - 1) + recurse(n - 2)
+///     }
+/// });
+///
+/// assert_eq!(fib.get(90), 2880067194370816120);
+/// ```
+pub struct Memo<K, V, F> {
+    f: F,
+    cache: Cache<K, V>,
+}
+
+/// Cache hit/miss counters of a [`Memo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+}
+
+struct Cache<K, V> {
+    map: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K, V, F> Memo<K, V, F>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Memo {
+            f,
+            cache: Cache {
+                map: HashMap::new(),
+                hits: 0,
+                misses: 0,
+            },
+        }
+    }
+
+    /// Returns the cached value for `key` or computes it.
+    pub fn get(&mut self, key: K) -> V {
+        Self::lookup(&self.f, &mut self.cache, key)
+    }
+
+    fn lookup(f: &F, cache: &mut Cache<K, V>, key: K) -> V {
+        if let Some(value) = cache.map.get(&key) {
+            cache.hits += 1;
+            return value.clone();
+        }
+
+        cache.misses += 1;
+        let value = f(&mut |k| Self::lookup(f, cache, k), key.clone());
+        cache.map.insert(key, value.clone());
+
+        value
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            hits: self.cache.hits,
+            misses: self.cache.misses,
+            entries: self.cache.map.len(),
+        }
+    }
+
+    /// Drops all cached values and resets the statistics.
+    pub fn clear(&mut self) {
+        self.cache.map.clear();
+        self.cache.hits = 0;
+        self.cache.misses = 0;
+    }
+}
+
+#[test]
+fn memo_stats() {
+    let mut fib = Memo::new(|recurse: &mut dyn FnMut(u64) -> u64, n: u64| {
+        if n < 2 {
+            n
+        } else {
+            recurse(n - 1) + recurse(n - 2)
+        }
+    });
+
+    assert_eq!(fib.get(10), 55);
+    assert_eq!(
+        fib.stats(),
+        MemoStats {
+            hits: 8,
+            misses: 11,
+            entries: 11
+        }
+    );
+
+    // second call is answered from the cache
+    assert_eq!(fib.get(10), 55);
+    assert_eq!(fib.stats().hits, 9);
+
+    fib.clear();
+    assert_eq!(fib.stats(), MemoStats::default());
+}
+
+#[test]
+fn memo_tuple_key() {
+    // lattice paths in a grid, keyed by (x, y)
+    let mut paths = Memo::new(
+        |recurse: &mut dyn FnMut((u32, u32)) -> u64, (x, y): (u32, u32)| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                recurse((x - 1, y)) + recurse((x, y - 1))
+            }
+        },
+    );
+
+    assert_eq!(paths.get((20, 20)), 137846528820);
+}
@@ -0,0 +1,221 @@
+use std::{error::Error, fmt, marker::PhantomData, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A run of digits that does not fit into the requested type.
+    InvalidNumber(String),
+    /// No line starting with `header:` was found.
+    MissingHeader(String),
+    /// The expected delimiter does not occur.
+    MissingDelimiter(char),
+}
+
+/// Error of the parsing helpers, `offset` is the byte offset where the problem was
+/// found (its length if the end was reached) into the string given to the helper.
+/// That is often a slice like a line or a section, not the whole input, so callers
+/// parsing line by line prefix their errors with the line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::InvalidNumber(s) => write!(f, "invalid number `{}`", s)?,
+            ParseErrorKind::MissingHeader(h) => write!(f, "missing `{}:` section", h)?,
+            ParseErrorKind::MissingDelimiter(c) => write!(f, "missing delimiter `{}`", c)?,
+        }
+        write!(f, " at offset {}", self.offset)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Iterator over the numbers in a string, created by [`uints`], [`signed_ints`] and [`ints`].
+pub struct Numbers<'a, T> {
+    s: &'a str,
+    pos: usize,
+    signed: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: FromStr> Iterator for Numbers<'a, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.s.as_bytes();
+
+        // skip everything that does not start a number
+        while self.pos < bytes.len() {
+            let b = bytes[self.pos];
+            let is_sign = self.signed
+                && (b == b'-' || b == b'+')
+                && bytes.get(self.pos + 1).is_some_and(u8::is_ascii_digit);
+
+            if b.is_ascii_digit() || is_sign {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        if self.pos == bytes.len() {
+            return None;
+        }
+
+        // consume sign and digits
+        let start = self.pos;
+        self.pos += 1;
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+
+        let number_str = &self.s[start..self.pos];
+        Some(number_str.parse().map_err(|_| ParseError {
+            offset: start,
+            kind: ParseErrorKind::InvalidNumber(number_str.to_owned()),
+        }))
+    }
+}
+
+/// All runs of ASCII digits in `s`, signs are treated like any other separator.
+pub fn uints<T: FromStr>(s: &str) -> Numbers<'_, T> {
+    Numbers {
+        s,
+        pos: 0,
+        signed: false,
+        _marker: PhantomData,
+    }
+}
+
+/// All runs of ASCII digits in `s`, including a directly preceding `-` or `+`.
+pub fn signed_ints<T: FromStr>(s: &str) -> Numbers<'_, T> {
+    Numbers {
+        s,
+        pos: 0,
+        signed: true,
+        _marker: PhantomData,
+    }
+}
+
+/// Shorthand for [`signed_ints`] as `i64`.
+pub fn ints(s: &str) -> Numbers<'_, i64> {
+    signed_ints(s)
+}
+
+/// Splits `Header: data` at the first colon into the trimmed header and data.
+pub fn split_header(s: &str) -> Result<(&str, &str), ParseError> {
+    s.split_once(':')
+        .map(|(header, data)| (header.trim(), data.trim()))
+        .ok_or(ParseError {
+            offset: s.len(),
+            kind: ParseErrorKind::MissingDelimiter(':'),
+        })
+}
+
+/// Finds the line `header: data` in `input` and returns its trimmed data.
+pub fn section<'a>(input: &'a str, header: &str) -> Result<&'a str, ParseError> {
+    input
+        .lines()
+        .find_map(|line| {
+            line.trim_start()
+                .strip_prefix(header)?
+                .trim_start()
+                .strip_prefix(':')
+                .map(str::trim)
+        })
+        .ok_or(ParseError {
+            offset: input.len(),
+            kind: ParseErrorKind::MissingHeader(header.to_owned()),
+        })
+}
+
+/// Iterator over the blocks of lines in a string, created by [`blocks`].
+pub struct Blocks<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut start = None;
+        let mut end = self.pos;
+
+        for line in self.s[self.pos..].split_inclusive('\n') {
+            let line_start = self.pos;
+            self.pos += line.len();
+
+            if line.trim().is_empty() {
+                // blank line ends a started block, otherwise skip it
+                if start.is_some() {
+                    break;
+                }
+            } else {
+                start.get_or_insert(line_start);
+                end = line_start + line.trim_end().len();
+            }
+        }
+
+        start.map(|start| &self.s[start..end])
+    }
+}
+
+/// Splits `input` into blocks separated by blank (or whitespace only) lines.
+pub fn blocks(input: &str) -> Blocks<'_> {
+    Blocks { s: input, pos: 0 }
+}
+
+#[test]
+fn numbers() {
+    let line = "Card  3: -1 21 +53 | 69-82 300";
+
+    let unsigned: Vec<u32> = uints(line).collect::<Result<_, _>>().unwrap();
+    assert_eq!(unsigned, vec![3, 1, 21, 53, 69, 82, 300]);
+
+    let signed: Vec<i64> = ints(line).collect::<Result<_, _>>().unwrap();
+    assert_eq!(signed, vec![3, -1, 21, 53, 69, -82, 300]);
+
+    let err = uints::<u8>(line)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ParseError {
+            offset: 27,
+            kind: ParseErrorKind::InvalidNumber("300".to_owned())
+        }
+    );
+}
+
+#[test]
+fn sections_and_blocks() {
+    let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n  \n\nsoil-to-fertilizer map:\n0 15 37\n";
+
+    assert_eq!(section(input, "seeds"), Ok("79 14"));
+    assert_eq!(section(input, "soil-to-fertilizer map"), Ok(""));
+
+    let err = section(input, "Time").unwrap_err();
+    assert_eq!(err.offset, input.len());
+
+    let blocks: Vec<&str> = blocks(input).collect();
+    assert_eq!(
+        blocks,
+        vec![
+            "seeds: 79 14",
+            "seed-to-soil map:\n50 98 2\n52 50 48",
+            "soil-to-fertilizer map:\n0 15 37",
+        ]
+    );
+
+    assert_eq!(
+        split_header(blocks[1]),
+        Ok(("seed-to-soil map", "50 98 2\n52 50 48"))
+    );
+
+    // offsets are into the slice that was parsed, not the whole input
+    let err = section(blocks[1], "seeds").unwrap_err();
+    assert_eq!(err.offset, blocks[1].len());
+}
@@ -0,0 +1,348 @@
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolynomialError {
+    /// No samples given.
+    Empty,
+    /// The differences of the samples never became zero, so the samples don't
+    /// determine a polynomial (any `n` samples fit a polynomial of degree `n - 1`).
+    NotPolynomial { samples: usize },
+    /// An intermediate value does not fit into `i128`.
+    Overflow,
+}
+
+impl fmt::Display for PolynomialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolynomialError::Empty => write!(f, "no samples to fit"),
+            PolynomialError::NotPolynomial { samples } => {
+                write!(
+                    f,
+                    "{} samples are not enough to verify a polynomial",
+                    samples
+                )
+            }
+            PolynomialError::Overflow => write!(f, "overflow in rational arithmetic"),
+        }
+    }
+}
+
+impl Error for PolynomialError {}
+
+/// Exact fraction `num / den`, always reduced and with positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// Panics if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "denominator of a rational must not be zero");
+        let g = gcd(num, den);
+        let sign = den.signum();
+        Rational {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        // a/b + c/d = (a * d/g + c * b/g) / (b/g * d)
+        let g = gcd(self.den, other.den);
+        let num = self
+            .num
+            .checked_mul(other.den / g)?
+            .checked_add(other.num.checked_mul(self.den / g)?)?;
+        let den = (self.den / g).checked_mul(other.den)?;
+        Some(Rational::new(num, den))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(-other)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // reduce crosswise first to keep the products small
+        let g1 = gcd(self.num, other.den).max(1);
+        let g2 = gcd(other.num, self.den).max(1);
+        let num = (self.num / g1).checked_mul(other.num / g2)?;
+        let den = (self.den / g2).checked_mul(other.den / g1)?;
+        Some(Rational::new(num, den))
+    }
+
+    /// `None` on overflow or division by zero.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.num == 0 {
+            return None;
+        }
+        self.checked_mul(Rational::new(other.den, other.num))
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational {
+            num: value as i128,
+            den: 1,
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        self.checked_add(other).expect("rational overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self.checked_sub(other).expect("rational overflow")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        self.checked_mul(other).expect("rational overflow")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, other: Rational) -> Rational {
+        assert!(other.num != 0, "division of a rational by zero");
+        self.checked_div(other).expect("rational overflow")
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross multiplying keeps the order
+        if let (Some(left), Some(right)) = (
+            self.num.checked_mul(other.den),
+            other.num.checked_mul(self.den),
+        ) {
+            return left.cmp(&right);
+        }
+
+        // the products overflow, compare a/b and c/d as continued fractions instead:
+        // first the integer parts, then the remainders by their reciprocals, which
+        // reverses the order. The numbers only get smaller, so nothing overflows.
+        let (mut a, mut b, mut c, mut d) = (self.num, self.den, other.num, other.den);
+        let mut reversed = false;
+
+        loop {
+            let order = match a.div_euclid(b).cmp(&c.div_euclid(d)) {
+                Ordering::Equal => {
+                    let (rest_a, rest_c) = (a.rem_euclid(b), c.rem_euclid(d));
+                    if rest_a == 0 || rest_c == 0 {
+                        rest_a.cmp(&rest_c)
+                    } else {
+                        (a, b, c, d) = (b, rest_a, d, rest_c);
+                        reversed = !reversed;
+                        continue;
+                    }
+                }
+                order => order,
+            };
+
+            return if reversed { order.reverse() } else { order };
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// Polynomial through equally spaced samples, sample `i` lies at position `i`.
+///
+/// Stored in Newton form `p(x) = sum of diffs[k] * binomial(x, k)`, where
+/// `diffs[k]` is the first element of the k-th difference sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    diffs: Vec<i128>,
+}
+
+impl Polynomial {
+    /// Fits the polynomial of minimal degree through `samples`.
+    ///
+    /// Fails if the differences don't reach an all zero sequence while there are
+    /// still samples left to check it, i.e. the degree would have to be `samples.len() - 1`.
+    pub fn fit(samples: &[i64]) -> Result<Polynomial, PolynomialError> {
+        if samples.is_empty() {
+            return Err(PolynomialError::Empty);
+        }
+
+        let mut sequence: Vec<i128> = samples.iter().map(|&x| x as i128).collect();
+        let mut diffs = vec![];
+
+        while !sequence.is_empty() {
+            if sequence.iter().all(|&x| x == 0) {
+                return Ok(Polynomial { diffs });
+            }
+
+            diffs.push(sequence[0]);
+            sequence = sequence
+                .windows(2)
+                .map(|w| w[1].checked_sub(w[0]))
+                .collect::<Option<_>>()
+                .ok_or(PolynomialError::Overflow)?;
+        }
+
+        Err(PolynomialError::NotPolynomial {
+            samples: samples.len(),
+        })
+    }
+
+    /// Degree of the polynomial, `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.diffs.len().checked_sub(1)
+    }
+
+    /// Value at an arbitrary (also negative or fractional) position.
+    pub fn eval(&self, x: Rational) -> Result<Rational, PolynomialError> {
+        // nested Newton form: d0 + x/1 * (d1 + (x-1)/2 * (d2 + (x-2)/3 * (...)))
+        let mut value = Rational::ZERO;
+
+        for (k, &diff) in self.diffs.iter().enumerate().rev() {
+            let k = k as i128;
+            value = x
+                .checked_sub(Rational::new(k, 1))
+                .and_then(|factor| value.checked_mul(factor))
+                .and_then(|v| v.checked_div(Rational::new(k + 1, 1)))
+                .and_then(|v| v.checked_add(Rational::new(diff, 1)))
+                .ok_or(PolynomialError::Overflow)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Value at an integer position, always an integer since the samples are.
+    pub fn value_at(&self, i: i64) -> Result<i128, PolynomialError> {
+        Ok(self
+            .eval(Rational::from(i))?
+            .to_integer()
+            .expect("integer samples give integer values at integer positions"))
+    }
+}
+
+#[test]
+fn rational() {
+    let a = Rational::new(6, -4);
+    assert_eq!((a.numer(), a.denom()), (-3, 2));
+    assert_eq!(a + Rational::new(1, 2), Rational::from(-1));
+    assert_eq!(a * a, Rational::new(9, 4));
+    assert_eq!(a / Rational::new(3, 4), Rational::from(-2));
+    assert!(a < Rational::new(-4, 3));
+    assert_eq!(Rational::new(9, 4).to_string(), "9/4");
+    assert_eq!(Rational::new(i128::MAX, 1).checked_add(Rational::ONE), None);
+}
+
+#[test]
+fn compare_near_limits() {
+    let max = i128::MAX;
+
+    // 1 + 1/(max - 1) < 1 + 1/(max - 2), the cross products overflow
+    let a = Rational::new(max, max - 1);
+    let b = Rational::new(max - 1, max - 2);
+    assert!(a < b);
+    assert!(-a > -b);
+    assert!(a > Rational::ONE);
+    assert_eq!(a.cmp(&a), Ordering::Equal);
+
+    // 2 - 1/(m + 1) > 2 - 1/m, the continued fractions only differ in the third term
+    let m = max / 2;
+    let c = Rational::new(2 * m + 1, m + 1);
+    let d = Rational::new(2 * m - 1, m);
+    assert_eq!(c.cmp(&d), Ordering::Greater);
+    assert_eq!(d.cmp(&c), Ordering::Less);
+
+    assert!(Rational::new(max, 3) < Rational::new(max, 2));
+    assert!(Rational::new(-max, 2) < Rational::new(-max + 1, 3));
+    assert!(Rational::new(max, max - 1) < Rational::new(max, 1));
+    assert!(Rational::new(-max, max - 1) < Rational::new(1, max));
+}
+
+#[test]
+fn fit_and_extrapolate() {
+    let p = Polynomial::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+    assert_eq!(p.degree(), Some(3));
+    assert_eq!(p.value_at(6), Ok(68));
+    assert_eq!(p.value_at(-1), Ok(5));
+    assert_eq!(p.value_at(2), Ok(16));
+
+    // p(x) = x^2
+    let square = Polynomial::fit(&[0, 1, 4, 9]).unwrap();
+    assert_eq!(
+        square.value_at(1_000_000_000),
+        Ok(1_000_000_000_000_000_000)
+    );
+    assert_eq!(square.eval(Rational::new(1, 2)), Ok(Rational::new(1, 4)));
+    assert_eq!(square.eval(Rational::new(-7, 3)), Ok(Rational::new(49, 9)));
+
+    assert_eq!(Polynomial::fit(&[0, 0]).unwrap().degree(), None);
+    assert_eq!(
+        Polynomial::fit(&[1, 2, 4, 8, 16]),
+        Err(PolynomialError::NotPolynomial { samples: 5 })
+    );
+    assert_eq!(Polynomial::fit(&[]), Err(PolynomialError::Empty));
+}